@@ -1,6 +1,5 @@
 use cgmath::*;
 use kiwi::{component::Component, impl_component, utils::types::TypeId, world::World};
-use rayon::prelude::*;
 
 #[derive(Debug, Copy, Clone)]
 struct Transform(Matrix4<f32>);
@@ -45,14 +44,14 @@ impl Benchmark {
     }
 
     pub fn run(&mut self) {
-        let query = self.world.query::<(&mut Position, &mut Transform), 2>();
-        query.par_bridge().for_each(|(pos, mat)| {
-            use cgmath::Transform;
-            for _ in 0..100 {
-                mat.0 = mat.0.invert().unwrap();
-            }
-
-            pos.0 = mat.0.transform_vector(pos.0);
-        });
+        self.world
+            .par_for_each::<(&mut Position, &mut Transform), 2>(64, |(pos, mat)| {
+                use cgmath::Transform;
+                for _ in 0..100 {
+                    mat.0 = mat.0.invert().unwrap();
+                }
+
+                pos.0 = mat.0.transform_vector(pos.0);
+            });
     }
 }
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use crate::utils::types::TypeId;
+
+/// Description of the data a system reads and writes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Access {
+    reads_world: bool,
+    writes_world: bool,
+    components_read: HashSet<TypeId>,
+    components_write: HashSet<TypeId>,
+    resources_read: HashSet<TypeId>,
+    resources_write: HashSet<TypeId>,
+    non_send: bool,
+    /// Whether the system defers changes to the world until they are applied
+    deferred: bool,
+}
+
+impl Access {
+    /// Marks the whole world as read
+    pub fn read_world(&mut self) {
+        self.reads_world = true;
+    }
+
+    /// Marks the whole world as written
+    pub fn write_world(&mut self) {
+        self.writes_world = true;
+    }
+
+    pub fn read_component(&mut self, id: TypeId) {
+        self.components_read.insert(id);
+    }
+
+    pub fn write_component(&mut self, id: TypeId) {
+        self.components_write.insert(id);
+    }
+
+    pub fn read_resource(&mut self, id: TypeId) {
+        self.resources_read.insert(id);
+    }
+
+    pub fn write_resource(&mut self, id: TypeId) {
+        self.resources_write.insert(id);
+    }
+
+    /// Marks that the system has to run on the thread
+    /// which created the world
+    pub fn set_non_send(&mut self) {
+        self.non_send = true;
+    }
+
+    pub fn is_non_send(&self) -> bool {
+        self.non_send
+    }
+
+    /// Marks that the system stores changes which are applied later
+    pub fn set_deferred(&mut self) {
+        self.deferred = true;
+    }
+
+    pub fn is_deferred(&self) -> bool {
+        self.deferred
+    }
+
+    /// Adds all accesses of `other` to `self`
+    pub fn extend(&mut self, other: &Access) {
+        self.reads_world |= other.reads_world;
+        self.writes_world |= other.writes_world;
+        self.components_read.extend(other.components_read.iter());
+        self.components_write.extend(other.components_write.iter());
+        self.resources_read.extend(other.resources_read.iter());
+        self.resources_write.extend(other.resources_write.iter());
+        self.non_send |= other.non_send;
+        self.deferred |= other.deferred;
+    }
+
+    /// Returns true if the access does not write anything
+    pub fn is_read_only(&self) -> bool {
+        !self.writes_world && self.components_write.is_empty() && self.resources_write.is_empty()
+    }
+
+    /// Returns true if systems with `self` and `other` accesses
+    /// can run at the same time
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        if self.writes_world || other.writes_world {
+            return false;
+        }
+        if self.reads_world {
+            return other.is_read_only();
+        }
+        if other.reads_world {
+            return self.is_read_only();
+        }
+        self.components_write.is_disjoint(&other.components_read)
+            && self.components_write.is_disjoint(&other.components_write)
+            && other.components_write.is_disjoint(&self.components_read)
+            && self.resources_write.is_disjoint(&other.resources_read)
+            && self.resources_write.is_disjoint(&other.resources_write)
+            && other.resources_write.is_disjoint(&self.resources_read)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn access_components_compatibility() {
+        let mut read_u8 = Access::default();
+        read_u8.read_component(TypeId::of::<u8>());
+
+        let mut read_u8_2 = Access::default();
+        read_u8_2.read_component(TypeId::of::<u8>());
+
+        let mut write_u8 = Access::default();
+        write_u8.write_component(TypeId::of::<u8>());
+
+        let mut write_u16 = Access::default();
+        write_u16.write_component(TypeId::of::<u16>());
+
+        assert!(read_u8.is_compatible(&read_u8_2));
+        assert!(!read_u8.is_compatible(&write_u8));
+        assert!(!write_u8.is_compatible(&read_u8));
+        assert!(!write_u8.is_compatible(&write_u8));
+        assert!(write_u8.is_compatible(&write_u16));
+        assert!(read_u8.is_compatible(&write_u16));
+    }
+
+    #[test]
+    fn access_resources_compatibility() {
+        let mut read_u8 = Access::default();
+        read_u8.read_resource(TypeId::of::<u8>());
+
+        let mut write_u8 = Access::default();
+        write_u8.write_resource(TypeId::of::<u8>());
+
+        let mut write_u8_component = Access::default();
+        write_u8_component.write_component(TypeId::of::<u8>());
+
+        assert!(read_u8.is_compatible(&read_u8));
+        assert!(!read_u8.is_compatible(&write_u8));
+        assert!(!write_u8.is_compatible(&write_u8));
+        assert!(write_u8.is_compatible(&write_u8_component));
+    }
+
+    #[test]
+    fn access_world_compatibility() {
+        let mut read_world = Access::default();
+        read_world.read_world();
+
+        let mut write_world = Access::default();
+        write_world.write_world();
+
+        let mut read_u8 = Access::default();
+        read_u8.read_component(TypeId::of::<u8>());
+
+        let mut write_u8 = Access::default();
+        write_u8.write_resource(TypeId::of::<u8>());
+
+        assert!(read_world.is_compatible(&read_world));
+        assert!(read_world.is_compatible(&read_u8));
+        assert!(!read_world.is_compatible(&write_u8));
+        assert!(!write_u8.is_compatible(&read_world));
+        assert!(!write_world.is_compatible(&read_u8));
+        assert!(!read_u8.is_compatible(&write_world));
+        assert!(!write_world.is_compatible(&Access::default()));
+    }
+}
@@ -211,6 +211,23 @@ impl BlobVec {
         }
     }
 
+    /// Removes the object at `index` by moving the last object
+    /// in its place
+    /// Does not drop the removed object
+    ///
+    /// # Safety
+    /// - The index should be in range 0 to blobvec.len()
+    #[inline]
+    pub unsafe fn swap_remove_forget(&mut self, index: usize) {
+        let last = self.len() - 1;
+        if index != last {
+            let size = self.layout.size();
+            self.data
+                .copy_within(last * size..(last + 1) * size, index * size);
+        }
+        self.data.truncate(last * self.layout.size());
+    }
+
     /// Swaps the object at `index` with new object
     /// Returns the swapped object
     ///
@@ -765,6 +782,27 @@ mod test {
         assert_eq!(Rc::<A>::strong_count(&val_copy_swapped), 3);
     }
 
+    #[test]
+    fn blob_swap_remove_forget() {
+        let layout = Layout::new::<u32>();
+        let mut blob = BlobVec::new(layout, None);
+
+        unsafe { blob.push(0) };
+        unsafe { blob.push(1) };
+        unsafe { blob.push(2) };
+
+        unsafe { blob.swap_remove_forget(0) };
+        assert_eq!(blob.len(), 2);
+        assert_eq!(blob.data, [2, 0, 0, 0, 1, 0, 0, 0]);
+
+        unsafe { blob.swap_remove_forget(1) };
+        assert_eq!(blob.len(), 1);
+        assert_eq!(blob.data, [2, 0, 0, 0]);
+
+        unsafe { blob.swap_remove_forget(0) };
+        assert!(blob.is_empty());
+    }
+
     #[test]
     fn blob_as_mut_ptr() {
         let layout = Layout::new::<u32>();
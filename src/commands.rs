@@ -0,0 +1,235 @@
+use std::fmt::Debug;
+
+use crate::{
+    access::Access,
+    component::Component,
+    entity::{Entity, EntityGenerator},
+    events::Event,
+    resources::Resource,
+    system::{SystemParameter, SystemParameterCache, SystemParameterFetch},
+    world::{UnsafeWorldCell, World},
+};
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Operations on the world deferred until the queue is applied
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+}
+
+impl Debug for CommandQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("commands num: {}", self.commands.len()))
+    }
+}
+
+impl CommandQueue {
+    pub fn push(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Applies all commands in the order they were pushed
+    #[tracing::instrument(skip_all)]
+    pub fn apply(&mut self, world: &mut World) {
+        for command in self.commands.drain(..) {
+            command(world);
+        }
+    }
+}
+
+impl SystemParameterCache for CommandQueue {
+    fn empty() -> Self {
+        Self::default()
+    }
+}
+
+/// Records structural changes of the world
+/// Changes are applied after the system's stage finishes
+#[derive(Debug)]
+pub struct Commands<'world, 'cache> {
+    entities: &'world EntityGenerator,
+    queue: &'cache mut CommandQueue,
+}
+
+impl Commands<'_, '_> {
+    /// Reserves new entity without components
+    /// Entity can be used in other commands right away
+    pub fn spawn(&mut self) -> Entity {
+        self.entities.reserve()
+    }
+
+    /// Removes entity with all its components
+    pub fn despawn(&mut self, entity: Entity) {
+        self.add(move |world| {
+            if let Err(e) = world.despawn(entity) {
+                tracing::warn!("Could not despawn entity: {e}");
+            }
+        });
+    }
+
+    pub fn add_component<C: Component>(&mut self, entity: Entity, component: C) {
+        self.add(move |world| {
+            if let Err(e) = world.add_component(entity, component) {
+                tracing::warn!("Could not add component to entity {entity}: {e}");
+            }
+        });
+    }
+
+    pub fn remove_component<C: Component>(&mut self, entity: Entity) {
+        self.add(move |world| {
+            if let Err(e) = world.remove_component::<C>(entity) {
+                tracing::warn!("Could not remove component from entity {entity}: {e}");
+            }
+        });
+    }
+
+    pub fn add_resource<R: Resource>(&mut self, resource: R) {
+        self.add(move |world| world.add_resource(resource));
+    }
+
+    pub fn remove_resource<R: Resource>(&mut self) {
+        self.add(move |world| {
+            if let Err(e) = world.remove_resource::<R>() {
+                tracing::warn!("Could not remove resource: {e}");
+            }
+        });
+    }
+
+    /// Triggers observers of `E` when commands are applied
+    pub fn trigger<E: Event>(&mut self, event: E, entity: Entity) {
+        self.add(move |world| world.trigger(event, entity));
+    }
+
+    /// Adds custom command
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.push(command);
+    }
+}
+
+impl SystemParameter for Commands<'_, '_> {
+    type Fetch = CommandsFetch;
+}
+
+#[derive(Debug)]
+pub struct CommandsFetch;
+
+impl SystemParameterFetch for CommandsFetch {
+    type Item<'world, 'cache> = Commands<'world, 'cache>;
+    type Cache = CommandQueue;
+
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
+    ) -> Self::Item<'world, 'cache> {
+        Self::Item {
+            entities: world.entities(),
+            queue: cache,
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.set_deferred();
+    }
+
+    fn apply(cache: &mut Self::Cache, world: &mut World) {
+        cache.apply(world);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{query::Query, resources::ResMut, system::Systems};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Spawned(Vec<Entity>);
+    impl Resource for Spawned {}
+
+    #[test]
+    fn commands_deferred() {
+        fn spawn(mut commands: Commands, mut spawned: ResMut<Spawned>) {
+            let entity = commands.spawn();
+            commands.add_component(entity, 1u8);
+            commands.add_component(entity, 2u16);
+            spawned.0.push(entity);
+        }
+        fn check(query: Query<(&u8, &u16), 2>) {
+            // Commands of the spawn system are not applied yet
+            assert_eq!(query.iter().count(), 0);
+        }
+
+        let mut world = World::default();
+        world.add_resource(Spawned::default());
+
+        let mut systems = Systems::default();
+        systems.add_system(spawn);
+        systems.add_system(check);
+        systems.run(&mut world);
+
+        let spawned = world.get_resource::<Spawned>().unwrap().0.clone();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(world.get_component::<u8>(spawned[0]), Ok(&1));
+        assert_eq!(world.get_component::<u16>(spawned[0]), Ok(&2));
+    }
+
+    #[test]
+    fn commands_despawn_and_resources() {
+        #[derive(Debug)]
+        struct Despawned(u32);
+        impl Resource for Despawned {}
+
+        fn despawn(mut commands: Commands, query: Query<(Entity, &u8), 1>) {
+            for (entity, _) in query.iter() {
+                commands.despawn(entity);
+            }
+            commands.add_resource(Despawned(3));
+        }
+
+        let mut world = World::default();
+        for i in 0..3u8 {
+            let entity = world.create();
+            world.add_component(entity, i).unwrap();
+        }
+        let entity = world.create();
+        world.add_component(entity, 0u16).unwrap();
+
+        let mut systems = Systems::default();
+        systems.add_system(despawn);
+        systems.run(&mut world);
+
+        assert_eq!(world.query::<(&u8,), 1>().count(), 0);
+        assert_eq!(world.query::<(&u16,), 1>().count(), 1);
+        assert_eq!(world.get_resource::<Despawned>().unwrap().0, 3);
+    }
+
+    #[test]
+    fn commands_add_component_to_despawned() {
+        fn despawn_and_add(mut commands: Commands, query: Query<(Entity, &u8), 1>) {
+            for (entity, _) in query.iter() {
+                commands.despawn(entity);
+                commands.add_component(entity, 1u16);
+            }
+        }
+
+        let mut world = World::default();
+        let entity = world.create();
+        world.add_component(entity, 0u8).unwrap();
+
+        let mut systems = Systems::default();
+        systems.add_system(despawn_and_add);
+        systems.run(&mut world);
+
+        assert!(!world.entities().is_alive(&entity));
+        assert_eq!(world.query::<(&u16,), 1>().count(), 0);
+    }
+}
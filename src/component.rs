@@ -1,15 +1,19 @@
 use std::fmt::Debug;
 
 use crate::{
+    access::Access,
     blobvec::BlobVec,
     entity::Entity,
     utils::{macros::count, static_sort, types::TypeId},
 };
 
-trait ComponentRef {
+pub trait ComponentRef {
     type Component: Component;
+    type Slice<'a>;
 
     fn fetch(blob: &BlobVec, line: usize) -> Self;
+    fn fetch_slice(blob: &BlobVec) -> Self::Slice<'_>;
+    fn access(access: &mut Access);
 }
 
 impl<T> ComponentRef for &T
@@ -17,10 +21,19 @@ where
     T: Component,
 {
     type Component = T;
+    type Slice<'a> = &'a [T];
 
     fn fetch(blob: &BlobVec, line: usize) -> Self {
         unsafe { &*blob.get_ptr::<T>(line) }
     }
+
+    fn fetch_slice(blob: &BlobVec) -> Self::Slice<'_> {
+        unsafe { blob.as_slice() }
+    }
+
+    fn access(access: &mut Access) {
+        access.read_component(T::ID);
+    }
 }
 
 impl<T> ComponentRef for &mut T
@@ -28,13 +41,25 @@ where
     T: Component,
 {
     type Component = T;
+    type Slice<'a> = &'a mut [T];
 
     fn fetch(blob: &BlobVec, line: usize) -> Self {
         unsafe { &mut *blob.get_ptr_mut(line) }
     }
+
+    fn fetch_slice(blob: &BlobVec) -> Self::Slice<'_> {
+        unsafe {
+            let slice = blob.as_slice::<T>();
+            std::slice::from_raw_parts_mut(slice.as_ptr() as *mut T, slice.len())
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.write_component(T::ID);
+    }
 }
 
-pub trait Component: Sized + Debug + 'static {
+pub trait Component: Sized + Debug + Send + Sync + 'static {
     const ID: TypeId;
 }
 
@@ -65,7 +90,12 @@ pub trait ComponentTuple<const L: usize>: Sized + Debug + 'static {
     const IDS: [TypeId; L];
     const SORTED_IDS: [TypeId; L];
 
+    /// Tuple of component slices with the same layout as `Self`
+    type Chunk<'a>;
+
     fn fetch(entity: Entity, columns: &[&BlobVec; L], line: usize) -> Self;
+    fn fetch_chunk<'a>(entities: &'a [Entity], columns: &[&'a BlobVec; L]) -> Self::Chunk<'a>;
+    fn access(access: &mut Access);
 }
 
 macro_rules! impl_component_tuple {
@@ -89,6 +119,8 @@ macro_rules! impl_component_tuple {
                 static_sort(ids, 0, count!($($t)*) as isize - 1)
             };
 
+            type Chunk<'a> = ($(<$t as ComponentRef>::Slice<'a>,)*);
+
             fn fetch(_entity: Entity, columns: &[&BlobVec; {count!($($t)*)}], line: usize) -> Self {
                 let mut _index = 0;
                 (
@@ -101,6 +133,26 @@ macro_rules! impl_component_tuple {
                     ),*,
                 )
             }
+
+            fn fetch_chunk<'a>(
+                _entities: &'a [Entity],
+                columns: &[&'a BlobVec; {count!($($t)*)}],
+            ) -> Self::Chunk<'a> {
+                let mut _index = 0;
+                (
+                    $(
+                        {
+                            let a = $t::fetch_slice(columns[_index]);
+                            _index += 1;
+                            a
+                        }
+                    ),*,
+                )
+            }
+
+            fn access(access: &mut Access) {
+                $(<$t as ComponentRef>::access(access);)*
+            }
         }
     };
 }
@@ -137,6 +189,8 @@ macro_rules! impl_component_tuple_with_entity {
                 static_sort(ids, 0, count!($($t)*) as isize - 1)
             };
 
+            type Chunk<'a> = (&'a [Entity], $(<$t as ComponentRef>::Slice<'a>,)*);
+
             fn fetch(entity: Entity, columns: &[&BlobVec; {count!($($t)*)}], line: usize) -> Self {
                 let mut _index = 0;
                 (
@@ -150,6 +204,27 @@ macro_rules! impl_component_tuple_with_entity {
                     ),*,
                 )
             }
+
+            fn fetch_chunk<'a>(
+                entities: &'a [Entity],
+                columns: &[&'a BlobVec; {count!($($t)*)}],
+            ) -> Self::Chunk<'a> {
+                let mut _index = 0;
+                (
+                    entities,
+                    $(
+                        {
+                            let a = $t::fetch_slice(columns[_index]);
+                            _index += 1;
+                            a
+                        }
+                    ),*,
+                )
+            }
+
+            fn access(access: &mut Access) {
+                $(<$t as ComponentRef>::access(access);)*
+            }
         }
     };
 }
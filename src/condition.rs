@@ -0,0 +1,278 @@
+use crate::{
+    access::Access,
+    resources::{Res, Resource},
+    system::{FunctionSystem, SystemParameter, SystemParameterFunction},
+    world::{UnsafeWorldCell, World},
+};
+
+/// Read-only system which decides if other systems should run
+pub trait Condition: Send + 'static {
+    /// Initializes and evaluates the condition with exclusive
+    /// access to the world
+    fn evaluate(&mut self, world: &mut World) -> bool {
+        self.initialize(world);
+        // Safe because the world is borrowed exclusively
+        unsafe { self.evaluate_unsafe(world.as_unsafe_world_cell()) }
+    }
+
+    /// Evaluates the condition in the world shared with other systems
+    ///
+    /// # Safety
+    /// Same as [`System::run_unsafe`]
+    ///
+    /// [`System::run_unsafe`]: crate::system::System::run_unsafe
+    unsafe fn evaluate_unsafe(&mut self, world: UnsafeWorldCell) -> bool;
+
+    /// Components and resources the condition reads
+    fn access(&self) -> &Access;
+
+    /// Prepares the condition to be evaluated in the `world`
+    fn initialize(&mut self, _world: &mut World) {}
+}
+
+impl<S, P> Condition for FunctionSystem<S, P>
+where
+    S: SystemParameterFunction<P, Output = bool> + Send + 'static,
+    P: SystemParameter + 'static,
+{
+    unsafe fn evaluate_unsafe(&mut self, world: UnsafeWorldCell) -> bool {
+        self.run_function_unsafe(world)
+    }
+
+    fn access(&self) -> &Access {
+        FunctionSystem::access(self)
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        FunctionSystem::initialize(self, world)
+    }
+}
+
+/// Marker for [`IntoCondition`] implementation of [`Condition`] types
+pub struct ConditionMarker;
+
+pub trait IntoCondition<Marker>: Sized {
+    type Output: Condition;
+
+    fn into_condition(self) -> Self::Output;
+
+    /// Condition is true if both `self` and `other` are true
+    /// `other` is not evaluated if `self` is false
+    fn and<C, M>(self, other: C) -> AndCondition<Self::Output, C::Output>
+    where
+        C: IntoCondition<M>,
+    {
+        let (a, b) = (self.into_condition(), other.into_condition());
+        let mut access = a.access().clone();
+        access.extend(b.access());
+        AndCondition { a, b, access }
+    }
+
+    /// Condition is true if `self` or `other` is true
+    /// `other` is not evaluated if `self` is true
+    fn or<C, M>(self, other: C) -> OrCondition<Self::Output, C::Output>
+    where
+        C: IntoCondition<M>,
+    {
+        let (a, b) = (self.into_condition(), other.into_condition());
+        let mut access = a.access().clone();
+        access.extend(b.access());
+        OrCondition { a, b, access }
+    }
+
+    /// Condition is true if `self` is false
+    fn not(self) -> NotCondition<Self::Output> {
+        NotCondition {
+            condition: self.into_condition(),
+        }
+    }
+}
+
+impl<S, P> IntoCondition<P> for S
+where
+    S: SystemParameterFunction<P, Output = bool> + Send + 'static,
+    P: SystemParameter + 'static,
+{
+    type Output = FunctionSystem<S, P>;
+
+    /// # Panics
+    /// Panics if the function parameters write to the world
+    /// or defer changes, which are never applied for conditions
+    fn into_condition(self) -> Self::Output {
+        let condition = FunctionSystem::new(self);
+        assert!(
+            condition.access().is_read_only() && !condition.access().is_deferred(),
+            "Run condition {} must be read-only",
+            std::any::type_name::<S>()
+        );
+        condition
+    }
+}
+
+impl<C: Condition> IntoCondition<ConditionMarker> for C {
+    type Output = C;
+
+    fn into_condition(self) -> Self::Output {
+        self
+    }
+}
+
+pub struct AndCondition<A, B> {
+    a: A,
+    b: B,
+    access: Access,
+}
+
+impl<A: Condition, B: Condition> Condition for AndCondition<A, B> {
+    unsafe fn evaluate_unsafe(&mut self, world: UnsafeWorldCell) -> bool {
+        self.a.evaluate_unsafe(world) && self.b.evaluate_unsafe(world)
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.a.initialize(world);
+        self.b.initialize(world);
+    }
+}
+
+pub struct OrCondition<A, B> {
+    a: A,
+    b: B,
+    access: Access,
+}
+
+impl<A: Condition, B: Condition> Condition for OrCondition<A, B> {
+    unsafe fn evaluate_unsafe(&mut self, world: UnsafeWorldCell) -> bool {
+        self.a.evaluate_unsafe(world) || self.b.evaluate_unsafe(world)
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.a.initialize(world);
+        self.b.initialize(world);
+    }
+}
+
+pub struct NotCondition<C> {
+    condition: C,
+}
+
+impl<C: Condition> Condition for NotCondition<C> {
+    unsafe fn evaluate_unsafe(&mut self, world: UnsafeWorldCell) -> bool {
+        !self.condition.evaluate_unsafe(world)
+    }
+
+    fn access(&self) -> &Access {
+        self.condition.access()
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.condition.initialize(world)
+    }
+}
+
+/// True if resource `R` exists
+pub fn resource_exists<R: Resource>() -> impl Condition {
+    (|res: Option<Res<R>>| res.is_some()).into_condition()
+}
+
+/// True if resource `R` exists and is equal to `value`
+pub fn resource_equals<R: Resource + PartialEq>(value: R) -> impl Condition {
+    (move |res: Option<Res<R>>| res.is_some_and(|r| *r == value)).into_condition()
+}
+
+/// True if resource `R` exists and was added or mutated since
+/// the last evaluation of the condition
+pub fn resource_changed<R: Resource>() -> impl Condition {
+    (|res: Option<Res<R>>| res.is_some_and(|r| r.is_changed())).into_condition()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::resources::ResMut;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Game {
+        Menu,
+        Playing,
+    }
+    impl Resource for Game {}
+
+    #[test]
+    fn condition_combinators() {
+        let mut world = World::default();
+
+        let mut exists = resource_exists::<Game>().into_condition();
+        let mut playing = resource_equals(Game::Playing).into_condition();
+        let mut not_playing = resource_equals(Game::Playing).not().into_condition();
+        let mut menu_or_playing = resource_equals(Game::Menu)
+            .or(resource_equals(Game::Playing))
+            .into_condition();
+        let mut exists_and_not_menu = resource_exists::<Game>()
+            .and(resource_equals(Game::Menu).not())
+            .into_condition();
+
+        assert!(!exists.evaluate(&mut world));
+        assert!(!playing.evaluate(&mut world));
+        assert!(not_playing.evaluate(&mut world));
+        assert!(!menu_or_playing.evaluate(&mut world));
+        assert!(!exists_and_not_menu.evaluate(&mut world));
+
+        world.add_resource(Game::Menu);
+        assert!(exists.evaluate(&mut world));
+        assert!(!playing.evaluate(&mut world));
+        assert!(not_playing.evaluate(&mut world));
+        assert!(menu_or_playing.evaluate(&mut world));
+        assert!(!exists_and_not_menu.evaluate(&mut world));
+
+        *world.get_resource_mut::<Game>().unwrap() = Game::Playing;
+        assert!(exists.evaluate(&mut world));
+        assert!(playing.evaluate(&mut world));
+        assert!(!not_playing.evaluate(&mut world));
+        assert!(menu_or_playing.evaluate(&mut world));
+        assert!(exists_and_not_menu.evaluate(&mut world));
+    }
+
+    #[test]
+    fn condition_resource_changed() {
+        let mut world = World::default();
+        let mut changed = resource_changed::<Game>().into_condition();
+
+        assert!(!changed.evaluate(&mut world));
+        world.add_resource(Game::Menu);
+        assert!(changed.evaluate(&mut world));
+        assert!(!changed.evaluate(&mut world));
+
+        world.get_resource::<Game>().unwrap();
+        assert!(!changed.evaluate(&mut world));
+        *world.get_resource_mut::<Game>().unwrap() = Game::Playing;
+        assert!(changed.evaluate(&mut world));
+        assert!(!changed.evaluate(&mut world));
+    }
+
+    #[test]
+    #[should_panic]
+    fn condition_must_be_read_only() {
+        fn writes(_: ResMut<Game>) -> bool {
+            true
+        }
+        writes.into_condition();
+    }
+
+    #[test]
+    #[should_panic]
+    fn condition_must_not_defer() {
+        fn defers(_: crate::commands::Commands) -> bool {
+            true
+        }
+        defers.into_condition();
+    }
+}
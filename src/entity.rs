@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicIsize, Ordering};
 
 pub const MAX_ENTITIES: u16 = std::u16::MAX;
 
@@ -26,20 +27,32 @@ impl Entity {
 #[derive(Debug, Default)]
 pub struct EntityGenerator {
     generations: Vec<EntityGeneration>,
+    /// Whether entity with the id is created and not deleted
+    alive: Vec<bool>,
     pending: Vec<EntityId>,
+    /// Number of `pending` ids not reserved yet
+    /// Negative value is the number of new ids reserved
+    /// after all pending ids were taken
+    free_cursor: AtomicIsize,
 }
 
 impl EntityGenerator {
     pub fn new() -> Self {
         Self {
             generations: Vec::with_capacity(MAX_ENTITIES as usize),
+            alive: Vec::with_capacity(MAX_ENTITIES as usize),
             pending: Vec::with_capacity(MAX_ENTITIES as usize),
+            free_cursor: AtomicIsize::new(0),
         }
     }
 
+    /// Creates new entity
+    /// Reserved entities should be flushed before
     #[tracing::instrument(skip_all)]
     pub fn create(&mut self) -> Entity {
-        if let Some(id) = self.pending.pop() {
+        debug_assert!(!self.has_reserved(), "reserved entities are not flushed");
+        let entity = if let Some(id) = self.pending.pop() {
+            self.alive[id as usize] = true;
             Entity {
                 id,
                 gen: self.generations[id as usize],
@@ -47,17 +60,91 @@ impl EntityGenerator {
         } else {
             let id = self.generations.len() as u16;
             self.generations.push(0);
+            self.alive.push(true);
+            Entity { id, gen: 0 }
+        };
+        *self.free_cursor.get_mut() = self.pending.len() as isize;
+        entity
+    }
+
+    /// Reserves new entity through shared reference
+    /// Reserved entities become alive after [`EntityGenerator::flush`]
+    #[tracing::instrument(skip_all)]
+    pub fn reserve(&self) -> Entity {
+        let cursor = self.free_cursor.fetch_sub(1, Ordering::Relaxed);
+        if 0 < cursor {
+            let id = self.pending[cursor as usize - 1];
+            Entity {
+                id,
+                gen: self.generations[id as usize],
+            }
+        } else {
+            let id = (self.generations.len() as isize - cursor) as u16;
             Entity { id, gen: 0 }
         }
     }
 
+    /// Returns true if some entities were reserved since last flush
+    pub fn has_reserved(&self) -> bool {
+        self.free_cursor.load(Ordering::Relaxed) != self.pending.len() as isize
+    }
+
+    /// Makes all reserved entities alive and calls `f` for each of them
+    #[tracing::instrument(skip_all)]
+    pub fn flush(&mut self, mut f: impl FnMut(Entity)) {
+        let cursor = *self.free_cursor.get_mut();
+        let pending_left = cursor.max(0) as usize;
+        for id in self.pending.drain(pending_left..) {
+            self.alive[id as usize] = true;
+            f(Entity {
+                id,
+                gen: self.generations[id as usize],
+            });
+        }
+        if cursor < 0 {
+            let old_len = self.generations.len();
+            self.generations.resize(old_len + (-cursor) as usize, 0);
+            self.alive.resize(self.generations.len(), true);
+            for id in old_len..self.generations.len() {
+                f(Entity {
+                    id: id as u16,
+                    gen: 0,
+                });
+            }
+        }
+        *self.free_cursor.get_mut() = self.pending.len() as isize;
+    }
+
+    /// Returns true if entity was created and not deleted yet
+    pub fn is_alive(&self, e: &Entity) -> bool {
+        self.generations.get(e.id as usize) == Some(&e.gen) && self.alive[e.id as usize]
+    }
+
+    /// Alive entities in the order of their ids
+    pub fn iter_alive(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.generations
+            .iter()
+            .zip(&self.alive)
+            .enumerate()
+            .filter(|(_, (_, alive))| **alive)
+            .map(|(id, (gen, _))| Entity {
+                id: id as u16,
+                gen: *gen,
+            })
+    }
+
+    /// Deletes entity
+    /// Reserved entities should be flushed before
     #[tracing::instrument(skip_all)]
     pub fn delete(&mut self, e: &Entity) {
+        debug_assert!(!self.has_reserved(), "reserved entities are not flushed");
         let generation = self.generations.get_mut(e.id as usize).unwrap();
         if *generation != e.gen {
             return;
         }
         *generation += 1;
+        self.alive[e.id as usize] = false;
         self.pending.push(e.id);
+        *self.free_cursor.get_mut() = self.pending.len() as isize;
     }
 }
@@ -1,42 +1,107 @@
 use std::{fmt::Debug, marker::PhantomData};
 
 use crate::{
+    access::Access,
     resources::{ResMut, Resource},
-    system::{SystemParameter, SystemParameterFetch},
-    world::World,
+    system::{SystemParameter, SystemParameterCache, SystemParameterFetch},
+    utils::types::TypeId,
+    world::{UnsafeWorldCell, World},
 };
 
-pub trait Event: Debug + 'static {}
+pub trait Event: Debug + Send + Sync + 'static {}
 
+/// Double buffered queue of events
+/// Events live for two updates, so every system sees them
+/// regardless of its position relative to the writer
 #[derive(Debug, Clone)]
 pub struct Events<E: Event> {
-    pub events: Vec<E>,
+    /// Events sent before the last update
+    previous: Vec<E>,
+    /// Events sent after the last update
+    current: Vec<E>,
+    /// Number of events sent before the first event in `previous`
+    previous_start: usize,
 }
 
 impl<E: Event> Default for Events<E> {
     fn default() -> Self {
-        Self { events: vec![] }
+        Self {
+            previous: vec![],
+            current: vec![],
+            previous_start: 0,
+        }
     }
 }
 
 impl<E: Event> Resource for Events<E> {}
 
+impl<E: Event> Events<E> {
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+    }
+
+    /// Drops events from the previous update and moves events
+    /// of the current update into the previous one
+    pub fn update(&mut self) {
+        self.previous_start += self.previous.len();
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Removes all events
+    pub fn clear(&mut self) {
+        self.update();
+        self.update();
+    }
+
+    /// Number of events sent since creation
+    pub fn sent(&self) -> usize {
+        self.previous_start + self.previous.len() + self.current.len()
+    }
+
+    /// Events sent after `sent` events
+    /// Events which are already dropped are skipped
+    pub fn since(&self, sent: usize) -> impl Iterator<Item = &E> {
+        let current_start = self.previous_start + self.previous.len();
+        let previous_skip = sent.saturating_sub(self.previous_start);
+        let current_skip = sent.saturating_sub(current_start);
+        self.previous
+            .iter()
+            .skip(previous_skip)
+            .chain(self.current.iter().skip(current_skip))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.previous.iter().chain(self.current.iter())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty() && self.current.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+}
+
+/// Reads events sent since the last run of the system
 #[derive(Debug)]
 pub struct EventReader<'a, E: Event> {
     events: &'a Events<E>,
+    /// Number of events the system had read before this run
+    read: usize,
 }
 
 impl<'a, E: Event> EventReader<'a, E> {
     pub fn iter(&self) -> impl Iterator<Item = &E> {
-        self.events.events.iter()
+        self.events.since(self.read)
     }
 
     pub fn is_empty(&self) -> bool {
-        self.events.events.is_empty()
+        self.len() == 0
     }
 
     pub fn len(&self) -> usize {
-        self.events.events.len()
+        self.iter().count()
     }
 }
 
@@ -44,6 +109,26 @@ impl<'a, E: Event> SystemParameter for EventReader<'a, E> {
     type Fetch = EventReaderFetch<E>;
 }
 
+/// Number of events the system has read
+#[derive(Debug, Default)]
+pub struct EventCursor {
+    read: usize,
+}
+
+impl EventCursor {
+    /// Marks all `events` as read
+    /// Returns number of events read before
+    pub fn advance<E: Event>(&mut self, events: &Events<E>) -> usize {
+        std::mem::replace(&mut self.read, events.sent())
+    }
+}
+
+impl SystemParameterCache for EventCursor {
+    fn empty() -> Self {
+        Self::default()
+    }
+}
+
 #[derive(Debug)]
 pub struct EventReaderFetch<E: Event> {
     phantom: PhantomData<E>,
@@ -51,17 +136,28 @@ pub struct EventReaderFetch<E: Event> {
 
 impl<E: Event> SystemParameterFetch for EventReaderFetch<E> {
     type Item<'world, 'cache> = EventReader<'world, E>;
-    type Cache = ();
+    type Cache = EventCursor;
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        _: &'cache Self::Cache,
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache> {
-        Self::Item {
-            events: world
-                .get_resource::<Events<E>>()
-                .expect("couldn't find event type"),
-        }
+        let (events, _) = world
+            .get_resource_with_ticks::<Events<E>>()
+            .expect("couldn't find event type");
+        // all events are considered read after the system runs
+        let read = cache.advance(events);
+        Self::Item { events, read }
+    }
+
+    fn access(access: &mut Access) {
+        access.read_resource(TypeId::of::<Events<E>>());
+    }
+
+    /// Adds the event type if it does not exist yet
+    fn init_cache(world: &mut World, _: &'static str) -> Self::Cache {
+        world.add_event::<E>();
+        Self::Cache::default()
     }
 }
 
@@ -72,15 +168,15 @@ pub struct EventWriter<'a, E: Event> {
 
 impl<'a, E: Event> EventWriter<'a, E> {
     pub fn send(&mut self, event: E) {
-        self.events.events.push(event);
+        self.events.send(event);
     }
 
     pub fn is_empty(&self) -> bool {
-        self.events.events.is_empty()
+        self.events.is_empty()
     }
 
     pub fn len(&self) -> usize {
-        self.events.events.len()
+        self.events.len()
     }
 }
 
@@ -97,24 +193,28 @@ impl<E: Event> SystemParameterFetch for EventWriterFetch<E> {
     type Item<'world, 'cache> = EventWriter<'world, E>;
     type Cache = ();
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        _: &'cache Self::Cache,
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        _: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache> {
-        Self::Item {
-            events: world
-                .get_resource_mut::<Events<E>>()
-                .expect("couldn't find event type"),
-        }
+        let (events, _) = world
+            .get_resource_mut_with_ticks::<Events<E>>()
+            .expect("couldn't find event type");
+        Self::Item { events }
+    }
+
+    fn access(access: &mut Access) {
+        access.write_resource(TypeId::of::<Events<E>>());
+    }
+
+    /// Adds the event type if it does not exist yet
+    fn init_cache(world: &mut World, _: &'static str) -> Self::Cache {
+        world.add_event::<E>();
     }
 }
 
 pub fn clear_events<E: Event>(mut events: ResMut<Events<E>>) {
-    events
-        .get_mut()
-        .expect("couldn't find event type")
-        .events
-        .clear();
+    events.clear();
 }
 
 #[cfg(test)]
@@ -153,4 +253,114 @@ mod test {
 
         systems.run(&mut ecs);
     }
+
+    #[test]
+    fn events_added_on_initialize() {
+        #[derive(Debug)]
+        struct E;
+        impl Event for E {}
+
+        fn read_events(reader: EventReader<E>) {
+            assert!(reader.is_empty());
+        }
+
+        let mut ecs = World::default();
+        let mut systems = Systems::default();
+        systems.add_system(read_events);
+        systems.run(&mut ecs);
+
+        assert!(ecs.get_resource::<Events<E>>().is_ok());
+    }
+
+    #[test]
+    fn events_read_once() {
+        use crate::resources::{ResMut, Resource};
+
+        #[derive(Debug)]
+        struct E(u8);
+        impl Event for E {}
+
+        #[derive(Debug, Default)]
+        struct Read(Vec<(&'static str, u8)>);
+        impl Resource for Read {}
+
+        fn before(reader: EventReader<E>, mut read: ResMut<Read>) {
+            let read = &mut read.0;
+            read.extend(reader.iter().map(|e| ("before", e.0)));
+        }
+        fn write(mut writer: EventWriter<E>, mut i: crate::system::Local<u8>) {
+            if *i < 2 {
+                writer.send(E(*i));
+            }
+            *i += 1;
+        }
+        fn after(reader: EventReader<E>, mut read: ResMut<Read>) {
+            let read = &mut read.0;
+            read.extend(reader.iter().map(|e| ("after", e.0)));
+        }
+
+        let mut ecs = World::default();
+        ecs.add_resource(Read::default());
+        let mut systems = Systems::default();
+        systems.add_system(before);
+        systems.add_system(write);
+        systems.add_system(after);
+        systems.add_system(after);
+        for _ in 0..4 {
+            systems.run(&mut ecs);
+        }
+
+        assert_eq!(
+            ecs.get_resource::<Read>().unwrap().0,
+            [
+                ("after", 0),
+                ("after", 0),
+                ("before", 0),
+                ("after", 1),
+                ("after", 1),
+                ("before", 1),
+            ]
+        );
+        assert!(ecs.get_resource::<Events<E>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn events_update_not_changed() {
+        use crate::condition::{resource_changed, Condition, IntoCondition};
+
+        #[derive(Debug)]
+        struct E;
+        impl Event for E {}
+
+        let mut ecs = World::default();
+        ecs.add_event::<E>();
+        let mut changed = resource_changed::<Events<E>>().into_condition();
+        assert!(changed.evaluate(&mut ecs));
+
+        ecs.update_events();
+        assert!(!changed.evaluate(&mut ecs));
+    }
+
+    #[test]
+    fn events_since() {
+        #[derive(Debug, PartialEq)]
+        struct E(u8);
+        impl Event for E {}
+
+        let mut events = Events::default();
+        events.send(E(0));
+        events.send(E(1));
+        events.update();
+        events.send(E(2));
+        assert_eq!(events.sent(), 3);
+        assert_eq!(events.since(1).collect::<Vec<_>>(), [&E(1), &E(2)]);
+
+        events.update();
+        assert_eq!(events.since(0).collect::<Vec<_>>(), [&E(2)]);
+        assert_eq!(events.since(3).count(), 0);
+
+        events.clear();
+        assert!(events.is_empty());
+        assert_eq!(events.sent(), 3);
+    }
 }
@@ -0,0 +1,248 @@
+use std::fmt::Debug;
+#[cfg(feature = "multi_threaded")]
+use std::sync::Mutex;
+
+use crate::{
+    system::{System, SystemError},
+    world::World,
+};
+
+pub trait Executor: Debug + Default {
+    /// Prepares executor to run `systems`
+    /// Called every time the list of systems changes
+    /// `dependencies` contains indices of systems that have
+    /// to finish before each system starts
+    fn init<S: System>(&mut self, systems: &[S], dependencies: &[Vec<usize>]);
+
+    /// Runs all `systems` once
+    /// Systems have to be initialized in the `world`
+    /// Returns errors of failed systems with their indices
+    /// in the order of the systems
+    fn run<S: System>(&mut self, systems: &mut [S], world: &mut World)
+        -> Vec<(usize, SystemError)>;
+}
+
+#[cfg(not(feature = "multi_threaded"))]
+pub type DefaultExecutor = SingleThreadedExecutor;
+#[cfg(feature = "multi_threaded")]
+pub type DefaultExecutor = MultiThreadedExecutor;
+
+/// Runs systems one after another in order
+#[derive(Debug, Default)]
+pub struct SingleThreadedExecutor;
+
+impl Executor for SingleThreadedExecutor {
+    fn init<S: System>(&mut self, _systems: &[S], _dependencies: &[Vec<usize>]) {}
+
+    #[tracing::instrument(skip_all)]
+    fn run<S: System>(
+        &mut self,
+        systems: &mut [S],
+        world: &mut World,
+    ) -> Vec<(usize, SystemError)> {
+        systems
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, system)| system.run(world).err().map(|e| (i, e)))
+            .collect()
+    }
+}
+
+/// Runs systems with compatible accesses at the same time
+/// on the rayon thread pool
+/// Systems with conflicting accesses or explicit dependencies
+/// are run in order
+/// Systems with non send access run on the calling thread,
+/// so the executor has to be run on the thread which created the world
+#[cfg(feature = "multi_threaded")]
+#[derive(Debug, Default)]
+pub struct MultiThreadedExecutor {
+    /// Batch index of each system
+    /// All systems in a batch can run at the same time
+    batches: Vec<usize>,
+    batches_num: usize,
+}
+
+#[cfg(feature = "multi_threaded")]
+impl Executor for MultiThreadedExecutor {
+    #[tracing::instrument(skip_all)]
+    fn init<S: System>(&mut self, systems: &[S], dependencies: &[Vec<usize>]) {
+        self.batches = system_batches(systems, dependencies);
+        self.batches_num = self.batches.iter().max().map(|b| b + 1).unwrap_or(0);
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn run<S: System>(
+        &mut self,
+        systems: &mut [S],
+        world: &mut World,
+    ) -> Vec<(usize, SystemError)> {
+        let world = world.as_unsafe_world_cell();
+        let errors = Mutex::new(vec![]);
+        for batch in 0..self.batches_num {
+            // In place scope runs the closure on the calling thread,
+            // so non send systems run there while others run on the pool
+            rayon::in_place_scope(|scope| {
+                let errors = &errors;
+                let mut non_send = vec![];
+                for (i, (system, _)) in systems
+                    .iter_mut()
+                    .zip(self.batches.iter())
+                    .enumerate()
+                    .filter(|(_, (_, b))| **b == batch)
+                {
+                    if system.access().is_non_send() {
+                        non_send.push((i, system));
+                        continue;
+                    }
+                    scope.spawn(move |_| {
+                        // Safe because systems in one batch have compatible
+                        // accesses and are initialized before the run
+                        if let Err(e) = unsafe { system.run_unsafe(world) } {
+                            errors.lock().unwrap().push((i, e));
+                        }
+                    });
+                }
+                for (i, system) in non_send {
+                    // Safe for the same reason as above
+                    if let Err(e) = unsafe { system.run_unsafe(world) } {
+                        errors.lock().unwrap().push((i, e));
+                    }
+                }
+            });
+        }
+        let mut errors = errors.into_inner().unwrap();
+        errors.sort_unstable_by_key(|(i, _)| *i);
+        errors
+    }
+}
+
+/// Assigns each system to the first batch after all
+/// previous systems it conflicts with or depends on
+#[cfg(feature = "multi_threaded")]
+fn system_batches<S: System>(systems: &[S], dependencies: &[Vec<usize>]) -> Vec<usize> {
+    let mut batches: Vec<usize> = Vec::with_capacity(systems.len());
+    for (i, system) in systems.iter().enumerate() {
+        let batch = systems[..i]
+            .iter()
+            .zip(batches.iter())
+            .enumerate()
+            .filter(|(j, (other, _))| {
+                dependencies[i].contains(j) || !system.access().is_compatible(other.access())
+            })
+            .map(|(_, (_, batch))| batch + 1)
+            .max()
+            .unwrap_or(0);
+        batches.push(batch);
+    }
+    batches
+}
+
+#[cfg(all(test, feature = "multi_threaded"))]
+mod test {
+    use crate::{query::Query, system::IntoSystem};
+
+    use super::*;
+
+    #[test]
+    fn executor_system_batches() {
+        fn write_u8(_: Query<(&mut u8,), 1>) {}
+        fn read_u8(_: Query<(&u8,), 1>) {}
+        fn read_u8_u16(_: Query<(&u8, &u16), 2>) {}
+        fn write_u16(_: Query<(&mut u16,), 1>) {}
+
+        let systems: Vec<Box<dyn System>> = vec![
+            Box::new(write_u8.into_system()),
+            Box::new(read_u8.into_system()),
+            Box::new(write_u16.into_system()),
+            Box::new(read_u8_u16.into_system()),
+            Box::new(write_u8.into_system()),
+        ];
+
+        assert_eq!(system_batches(&systems, &vec![vec![]; 5]), [0, 1, 0, 1, 2]);
+        assert_eq!(
+            system_batches(&systems, &[vec![], vec![], vec![1], vec![], vec![]]),
+            [0, 1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn executor_multi_threaded_run() {
+        fn add_u8(query: Query<(&mut u8,), 1>) {
+            for (a,) in query.iter() {
+                *a += 1;
+            }
+        }
+        fn add_u16(query: Query<(&mut u16,), 1>) {
+            for (a,) in query.iter() {
+                *a += 1;
+            }
+        }
+        fn check(query: Query<(&u8, &u16), 2>) {
+            for (a, b) in query.iter() {
+                assert_eq!(*a, 2);
+                assert_eq!(*b, 2);
+            }
+        }
+
+        let mut world = World::default();
+        for _ in 0..100 {
+            let entity = world.create();
+            world.add_component(entity, 0u8).unwrap();
+            world.add_component(entity, 0u16).unwrap();
+        }
+
+        let mut systems: Vec<Box<dyn System>> = vec![
+            Box::new(add_u8.into_system()),
+            Box::new(add_u16.into_system()),
+            Box::new(add_u8.into_system()),
+            Box::new(add_u16.into_system()),
+            Box::new(check.into_system()),
+        ];
+
+        let mut executor = MultiThreadedExecutor::default();
+        for system in systems.iter_mut() {
+            system.initialize(&mut world);
+        }
+        executor.init(&systems, &vec![vec![]; 5]);
+        executor.run(&mut systems, &mut world);
+    }
+
+    #[test]
+    fn executor_non_send_on_calling_thread() {
+        use crate::resources::NonSendMut;
+        use std::thread::ThreadId;
+
+        fn record(mut threads: NonSendMut<Vec<ThreadId>>, _: Query<(&mut u8,), 1>) {
+            threads.push(std::thread::current().id());
+        }
+        fn add_u16(query: Query<(&mut u16,), 1>) {
+            for (a,) in query.iter() {
+                *a += 1;
+            }
+        }
+
+        let mut world = World::default();
+        let entity = world.create();
+        world.add_component(entity, 0u16).unwrap();
+        world.add_non_send_resource(Vec::<ThreadId>::new());
+
+        let mut systems: Vec<Box<dyn System>> = vec![
+            Box::new(add_u16.into_system()),
+            Box::new(record.into_system()),
+            Box::new(add_u16.into_system()),
+            Box::new(record.into_system()),
+        ];
+
+        let mut executor = MultiThreadedExecutor::default();
+        for system in systems.iter_mut() {
+            system.initialize(&mut world);
+        }
+        executor.init(&systems, &vec![vec![]; 4]);
+        assert!(executor.run(&mut systems, &mut world).is_empty());
+
+        let threads = world.get_non_send_resource::<Vec<ThreadId>>().unwrap();
+        assert_eq!(threads, &[std::thread::current().id(); 2]);
+        assert_eq!(world.get_component::<u16>(entity), Ok(&2));
+    }
+}
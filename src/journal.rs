@@ -0,0 +1,193 @@
+use std::collections::VecDeque;
+
+use crate::{entity::Entity, utils::types::TypeId};
+
+/// Number of changes kept in the journal by default
+pub const DEFAULT_JOURNAL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Spawned,
+    Despawned,
+    ComponentAdded(TypeId),
+    ComponentRemoved(TypeId),
+}
+
+/// Change of the entity set or of the entity archetype
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructuralChange {
+    /// World change tick at the moment of the change
+    pub tick: u32,
+    pub entity: Entity,
+    pub change: Change,
+}
+
+/// Position of a subscriber in the [`ChangeJournal`]
+#[derive(Debug)]
+pub struct ChangeCursor {
+    /// Number of changes recorded before the next unread one
+    read: usize,
+    missed: usize,
+}
+
+impl ChangeCursor {
+    /// Number of changes dropped from the journal before
+    /// the subscriber read them
+    pub fn missed(&self) -> usize {
+        self.missed
+    }
+}
+
+/// Ring buffer of the last structural changes of the world
+/// Changes are recorded only while someone is subscribed
+#[derive(Debug)]
+pub struct ChangeJournal {
+    changes: VecDeque<StructuralChange>,
+    capacity: usize,
+    /// Number of changes dropped from the front of the buffer
+    dropped: usize,
+    subscribers: usize,
+}
+
+impl Default for ChangeJournal {
+    fn default() -> Self {
+        Self {
+            changes: VecDeque::new(),
+            capacity: DEFAULT_JOURNAL_CAPACITY,
+            dropped: 0,
+            subscribers: 0,
+        }
+    }
+}
+
+impl ChangeJournal {
+    pub fn is_subscribed(&self) -> bool {
+        self.subscribers != 0
+    }
+
+    /// Subscriber receives changes recorded after this call
+    pub fn subscribe(&mut self) -> ChangeCursor {
+        self.subscribers += 1;
+        ChangeCursor {
+            read: self.recorded(),
+            missed: 0,
+        }
+    }
+
+    /// Journal is cleared when the last subscriber leaves
+    pub fn unsubscribe(&mut self, _cursor: ChangeCursor) {
+        self.subscribers -= 1;
+        if self.subscribers == 0 {
+            self.dropped += self.changes.len();
+            self.changes.clear();
+        }
+    }
+
+    /// Oldest changes are dropped once the journal has `capacity` changes
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.changes.len() > self.capacity {
+            self.changes.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    pub fn record(&mut self, tick: u32, entity: Entity, change: Change) {
+        if !self.is_subscribed() || self.capacity == 0 {
+            return;
+        }
+        if self.changes.len() == self.capacity {
+            self.changes.pop_front();
+            self.dropped += 1;
+        }
+        self.changes.push_back(StructuralChange {
+            tick,
+            entity,
+            change,
+        });
+    }
+
+    /// Changes the subscriber has not read yet
+    /// All of them are considered read after the call
+    pub fn read(&self, cursor: &mut ChangeCursor) -> impl Iterator<Item = &StructuralChange> {
+        cursor.missed += self.dropped.saturating_sub(cursor.read);
+        let skip = cursor.read.saturating_sub(self.dropped);
+        cursor.read = self.recorded();
+        self.changes.iter().skip(skip)
+    }
+
+    /// Number of changes recorded since creation
+    fn recorded(&self) -> usize {
+        self.dropped + self.changes.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{component::Component, world::World};
+
+    use super::*;
+
+    #[test]
+    fn journal_records_changes() {
+        let mut world = World::default();
+        let before = world.create();
+
+        let mut cursor = world.subscribe_changes();
+        let entity = world.create();
+        world.add_component(entity, 1u16).unwrap();
+        world.add_component(entity, 1u8).unwrap();
+        world.remove_component::<u8>(entity).unwrap();
+        world.despawn(entity).unwrap();
+        world.despawn(before).unwrap();
+        let reserved = world.reserve();
+        world.flush();
+
+        let changes = world
+            .read_changes(&mut cursor)
+            .map(|c| (c.entity, c.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                (entity, Change::Spawned),
+                (entity, Change::ComponentAdded(u16::ID)),
+                (entity, Change::ComponentAdded(u8::ID)),
+                (entity, Change::ComponentRemoved(u8::ID)),
+                (entity, Change::ComponentRemoved(u16::ID)),
+                (entity, Change::Despawned),
+                (before, Change::Despawned),
+                (reserved, Change::Spawned),
+            ]
+        );
+        assert_eq!(world.read_changes(&mut cursor).count(), 0);
+        world.unsubscribe_changes(cursor);
+    }
+
+    #[test]
+    fn journal_is_bounded() {
+        let mut journal = ChangeJournal::default();
+        let entity = World::default().create();
+
+        journal.record(0, entity, Change::Spawned);
+        let mut a = journal.subscribe();
+        journal.set_capacity(2);
+        for tick in 0..3 {
+            journal.record(tick, entity, Change::Spawned);
+        }
+        let mut b = journal.subscribe();
+        journal.record(3, entity, Change::Despawned);
+
+        let ticks = journal.read(&mut a).map(|c| c.tick).collect::<Vec<_>>();
+        assert_eq!(ticks, [2, 3]);
+        assert_eq!(a.missed(), 2);
+        let ticks = journal.read(&mut b).map(|c| c.tick).collect::<Vec<_>>();
+        assert_eq!(ticks, [3]);
+        assert_eq!(b.missed(), 0);
+
+        journal.unsubscribe(a);
+        journal.unsubscribe(b);
+        journal.record(4, entity, Change::Spawned);
+        assert!(journal.changes.is_empty());
+    }
+}
@@ -6,15 +6,24 @@
 #![feature(allocator_api)]
 #![allow(internal_features)]
 #![feature(core_intrinsics)]
-#![feature(fn_traits)]
 
+pub mod access;
 pub mod archetype;
 pub mod blobvec;
+pub mod commands;
 pub mod component;
+pub mod condition;
 pub mod entity;
 pub mod events;
+pub mod executor;
+pub mod journal;
+pub mod monitor;
+pub mod observer;
+pub mod pipe;
 pub mod query;
+pub mod removed;
 pub mod resources;
+pub mod schedule;
 pub mod sparse_set;
 pub mod system;
 pub mod table;
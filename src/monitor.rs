@@ -0,0 +1,205 @@
+use crate::{component::Component, entity::Entity, utils::types::TypeId};
+
+/// Components an entity must and must not have
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QueryFilter {
+    with: Vec<TypeId>,
+    without: Vec<TypeId>,
+}
+
+impl QueryFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<C: Component>(mut self) -> Self {
+        self.with.push(C::ID);
+        self
+    }
+
+    pub fn without<C: Component>(mut self) -> Self {
+        self.without.push(C::ID);
+        self
+    }
+
+    /// True if `components` satisfy the filter
+    pub fn matches(&self, components: &[TypeId]) -> bool {
+        self.with.iter().all(|id| components.contains(id))
+            && !self.without.iter().any(|id| components.contains(id))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorEvent {
+    /// Entity started matching the filter
+    Entered(Entity),
+    /// Entity stopped matching the filter or was despawned
+    Exited(Entity),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MonitorId(usize);
+
+#[derive(Debug)]
+struct QueryMonitor {
+    filter: QueryFilter,
+    events: Vec<MonitorEvent>,
+}
+
+/// Monitors collect events until they are drained
+#[derive(Debug, Default)]
+pub struct QueryMonitors {
+    monitors: Vec<Option<QueryMonitor>>,
+}
+
+impl QueryMonitors {
+    pub fn is_empty(&self) -> bool {
+        self.monitors.iter().all(|monitor| monitor.is_none())
+    }
+
+    /// `entities` are entities matching the filter at the moment
+    pub fn add(
+        &mut self,
+        filter: QueryFilter,
+        entities: impl Iterator<Item = Entity>,
+    ) -> MonitorId {
+        let monitor = QueryMonitor {
+            filter,
+            events: entities.map(MonitorEvent::Entered).collect(),
+        };
+        // ids are not reused, so removed monitors can't be
+        // confused with new ones
+        self.monitors.push(Some(monitor));
+        MonitorId(self.monitors.len() - 1)
+    }
+
+    /// Returns false if the monitor does not exist
+    pub fn remove(&mut self, id: MonitorId) -> bool {
+        self.monitors
+            .get_mut(id.0)
+            .and_then(|monitor| monitor.take())
+            .is_some()
+    }
+
+    pub fn filter(&self, id: MonitorId) -> Option<&QueryFilter> {
+        self.get(id).map(|monitor| &monitor.filter)
+    }
+
+    /// Takes all events collected since the last drain
+    pub fn drain(&mut self, id: MonitorId) -> Option<Vec<MonitorEvent>> {
+        self.monitors
+            .get_mut(id.0)
+            .and_then(|monitor| monitor.as_mut())
+            .map(|monitor| std::mem::take(&mut monitor.events))
+    }
+
+    /// Records entity moving from `old` to `new` set of components
+    /// `None` means the entity does not exist
+    pub fn transition(&mut self, entity: Entity, old: Option<&[TypeId]>, new: Option<&[TypeId]>) {
+        for monitor in self.monitors.iter_mut().flatten() {
+            let was = old.is_some_and(|old| monitor.filter.matches(old));
+            let is = new.is_some_and(|new| monitor.filter.matches(new));
+            match (was, is) {
+                (false, true) => monitor.events.push(MonitorEvent::Entered(entity)),
+                (true, false) => monitor.events.push(MonitorEvent::Exited(entity)),
+                _ => {}
+            }
+        }
+    }
+
+    fn get(&self, id: MonitorId) -> Option<&QueryMonitor> {
+        self.monitors.get(id.0).and_then(|monitor| monitor.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{impl_component, world::World};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Position(u32);
+    impl_component!(Position);
+
+    #[derive(Debug)]
+    struct Sprite(u32);
+    impl_component!(Sprite);
+
+    #[derive(Debug)]
+    struct Hidden(bool);
+    impl_component!(Hidden);
+
+    #[test]
+    fn monitor_enter_exit() {
+        let mut world = World::default();
+        let existing = world.create();
+        world.add_component(existing, Position(0)).unwrap();
+        world.add_component(existing, Sprite(0)).unwrap();
+
+        let filter = QueryFilter::new()
+            .with::<Position>()
+            .with::<Sprite>()
+            .without::<Hidden>();
+        let id = world.add_monitor(filter);
+        assert_eq!(
+            world.drain_monitor(id),
+            Ok(vec![MonitorEvent::Entered(existing)])
+        );
+
+        let entity = world.create();
+        world.add_component(entity, Position(1)).unwrap();
+        world.add_component(entity, Sprite(1)).unwrap();
+        world.add_component(entity, 1u8).unwrap();
+        world.add_component(entity, Hidden(true)).unwrap();
+        assert!(world.get_component::<Hidden>(entity).unwrap().0);
+        world.remove_component::<Hidden>(entity).unwrap();
+        world.despawn(entity).unwrap();
+        assert_eq!(world.get_component::<Sprite>(existing).unwrap().0, 0);
+        world.remove_component::<Sprite>(existing).unwrap();
+
+        assert_eq!(
+            world.drain_monitor(id),
+            Ok(vec![
+                MonitorEvent::Entered(entity),
+                MonitorEvent::Exited(entity),
+                MonitorEvent::Entered(entity),
+                MonitorEvent::Exited(entity),
+                MonitorEvent::Exited(existing),
+            ])
+        );
+        assert_eq!(world.drain_monitor(id), Ok(vec![]));
+
+        assert!(world.remove_monitor(id));
+        assert!(world.drain_monitor(id).is_err());
+        assert_eq!(world.get_component::<Position>(existing).unwrap().0, 0);
+    }
+
+    #[test]
+    fn monitor_initial_entities() {
+        let mut world = World::default();
+        let entities = (0..8).map(|_| world.create()).collect::<Vec<_>>();
+        for entity in entities.iter().step_by(2) {
+            world.add_component(*entity, Position(0)).unwrap();
+        }
+        world.despawn(entities[1]).unwrap();
+        world.add_component(entities[3], Hidden(true)).unwrap();
+
+        let id = world.add_monitor(QueryFilter::new().without::<Hidden>());
+        let expected = [0, 2, 4, 5, 6, 7].map(|i| MonitorEvent::Entered(entities[i]));
+        assert_eq!(world.drain_monitor(id), Ok(expected.to_vec()));
+
+        let id = world.add_monitor(QueryFilter::new().with::<Position>());
+        let expected = [0, 2, 4, 6].map(|i| MonitorEvent::Entered(entities[i]));
+        assert_eq!(world.drain_monitor(id), Ok(expected.to_vec()));
+    }
+
+    #[test]
+    fn monitor_filter_matches() {
+        let filter = QueryFilter::new().with::<u8>().without::<u16>();
+        assert!(filter.matches(&[u8::ID, u32::ID]));
+        assert!(!filter.matches(&[u8::ID, u16::ID]));
+        assert!(!filter.matches(&[u32::ID]));
+        assert!(QueryFilter::new().matches(&[]));
+    }
+}
@@ -0,0 +1,506 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Debug,
+    marker::PhantomData,
+};
+
+use crate::{
+    component::Component,
+    entity::Entity,
+    events::Event,
+    impl_component,
+    schedule::SystemId,
+    system::{SystemParameter, SystemParameterCache, SystemParameterFetch, SystemParameterItem},
+    utils::types::TypeId,
+    world::World,
+};
+
+/// Parent of the entity
+/// Triggers propagate from entities to their parents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(pub Entity);
+impl_component!(Parent);
+
+/// Event passed to observers with the entity it targets
+#[derive(Debug)]
+pub struct Trigger<'a, E: Event> {
+    event: &'a E,
+    entity: Entity,
+    propagate: &'a mut bool,
+}
+
+impl<E: Event> Trigger<'_, E> {
+    pub fn event(&self) -> &E {
+        self.event
+    }
+
+    /// Entity the event currently targets
+    /// Changes to parents when the event propagates
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Trigger observers of the [`Parent`] of the entity after
+    /// all observers of the entity run
+    pub fn propagate(&mut self, propagate: bool) {
+        *self.propagate = propagate;
+    }
+}
+
+/// Function with [`Trigger`] as the first parameter
+pub trait ObserverFunction<E: Event, Parameter: SystemParameter>: 'static {
+    fn run(&mut self, trigger: Trigger<E>, params: SystemParameterItem<Parameter>);
+}
+
+macro_rules! impl_observer_func {
+    ($($t:ident),*) => {
+        impl<F, E, $($t),*> ObserverFunction<E, ($($t, )*)> for F
+        where
+            F: FnMut(Trigger<E>, $($t),*) + 'static,
+            F: FnMut(Trigger<E>, $(SystemParameterItem<$t>),*) + 'static,
+            E: Event,
+            $($t: SystemParameter),*,
+        {
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            fn run(&mut self, trigger: Trigger<E>, params: SystemParameterItem<($($t, )*)>) {
+                fn call<E: Event, $($t),*>(
+                    mut f: impl FnMut(Trigger<E>, $($t),*),
+                    trigger: Trigger<E>,
+                    $($t: $t),*
+                ) {
+                    f(trigger, $($t),*)
+                }
+                let ($($t, )*) = params;
+                call(self, trigger, $($t),*)
+            }
+        }
+    };
+}
+
+impl<F, E> ObserverFunction<E, ()> for F
+where
+    F: FnMut(Trigger<E>) + 'static,
+    E: Event,
+{
+    fn run(&mut self, trigger: Trigger<E>, _: ()) {
+        self(trigger)
+    }
+}
+
+impl_observer_func!(P1);
+impl_observer_func!(P1, P2);
+impl_observer_func!(P1, P2, P3);
+impl_observer_func!(P1, P2, P3, P4);
+impl_observer_func!(P1, P2, P3, P4, P5);
+impl_observer_func!(P1, P2, P3, P4, P5, P6);
+
+/// Callback run right away when event `E` is triggered
+pub trait Observer<E: Event>: Send + 'static {
+    fn run(&mut self, trigger: Trigger<E>, world: &mut World);
+
+    fn name(&self) -> &'static str;
+
+    fn initialize(&mut self, world: &mut World);
+
+    fn apply_deferred(&mut self, world: &mut World);
+}
+
+pub struct ObserverSystem<S, E, Params: SystemParameter> {
+    function: S,
+    cache: <<Params as SystemParameter>::Fetch as SystemParameterFetch>::Cache,
+    is_initialized: bool,
+    params: PhantomData<fn(E) -> Params>,
+}
+
+impl<S, E, P> Observer<E> for ObserverSystem<S, E, P>
+where
+    S: ObserverFunction<E, P> + Send,
+    E: Event,
+    P: SystemParameter + 'static,
+{
+    #[tracing::instrument(skip_all)]
+    fn run(&mut self, trigger: Trigger<E>, world: &mut World) {
+        self.initialize(world);
+        let world = world.as_unsafe_world_cell().with_new_change_tick();
+        // Safe because the world is borrowed exclusively
+        let params = unsafe { P::Fetch::fetch(world, &mut self.cache) };
+        self.function.run(trigger, params);
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<S>()
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        if !self.is_initialized {
+            self.cache = P::Fetch::init_cache(world, std::any::type_name::<S>());
+            self.is_initialized = true;
+        }
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        P::Fetch::apply(&mut self.cache, world);
+    }
+}
+
+pub trait IntoObserver<E: Event, Marker> {
+    type Observer: Observer<E>;
+
+    fn into_observer(self) -> Self::Observer;
+}
+
+impl<S, E, P> IntoObserver<E, P> for S
+where
+    S: ObserverFunction<E, P> + Send,
+    E: Event,
+    P: SystemParameter + 'static,
+{
+    type Observer = ObserverSystem<S, E, P>;
+
+    fn into_observer(self) -> Self::Observer {
+        ObserverSystem {
+            function: self,
+            cache: <<P as SystemParameter>::Fetch as SystemParameterFetch>::Cache::empty(),
+            is_initialized: false,
+            params: PhantomData,
+        }
+    }
+}
+
+struct ObserverEntry<E: Event> {
+    id: SystemId,
+    /// Observer runs only for this entity if set
+    target: Option<Entity>,
+    observer: Box<dyn Observer<E>>,
+}
+
+/// Changes made to observers of the world while they run
+struct Running<E: Event> {
+    /// Observers taken out of the world
+    ids: Vec<SystemId>,
+    /// Running observers removed meanwhile
+    removed: Vec<SystemId>,
+    /// Triggers made meanwhile
+    pending: VecDeque<(E, Entity)>,
+}
+
+/// All observers of event `E` in the order they were added
+pub struct Observers<E: Event> {
+    entries: Vec<ObserverEntry<E>>,
+    /// Set if these are placeholder observers of the world
+    /// standing in for the running ones
+    running: Option<Running<E>>,
+}
+
+impl<E: Event> Default for Observers<E> {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            running: None,
+        }
+    }
+}
+
+impl<E: Event> Debug for Observers<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("observers num: {}", self.entries.len()))
+    }
+}
+
+impl<E: Event> Observers<E> {
+    pub fn add(&mut self, target: Option<Entity>, observer: impl Observer<E>) -> SystemId {
+        let id = SystemId::new();
+        self.entries.push(ObserverEntry {
+            id,
+            target,
+            observer: Box::new(observer),
+        });
+        id
+    }
+
+    /// Returns false if the observer does not exist
+    /// Running observers are removed after they finish
+    pub fn remove(&mut self, id: SystemId) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        if len != self.entries.len() {
+            return true;
+        }
+        match &mut self.running {
+            Some(running) if running.ids.contains(&id) && !running.removed.contains(&id) => {
+                running.removed.push(id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Moves observers from `other` after own observers
+    pub fn append(&mut self, other: &mut Self) {
+        self.entries.append(&mut other.entries);
+    }
+
+    /// Empty observers standing in for `self` while they run
+    /// They collect observers, removals and triggers made meanwhile
+    pub(crate) fn placeholder(&self) -> Self {
+        Self {
+            entries: vec![],
+            running: Some(Running {
+                ids: self.entries.iter().map(|entry| entry.id).collect(),
+                removed: vec![],
+                pending: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Queue of triggers if these are placeholder observers
+    pub(crate) fn pending_mut(&mut self) -> Option<&mut VecDeque<(E, Entity)>> {
+        self.running.as_mut().map(|running| &mut running.pending)
+    }
+
+    /// Applies observers and removals collected by the `placeholder`
+    /// Returns triggers collected by the `placeholder`
+    pub(crate) fn merge(&mut self, placeholder: &mut Self) -> VecDeque<(E, Entity)> {
+        let running = placeholder
+            .running
+            .as_mut()
+            .expect("merged observers are not a placeholder");
+        for id in running.removed.drain(..) {
+            self.remove(id);
+        }
+        self.entries.append(&mut placeholder.entries);
+        running.ids = self.entries.iter().map(|entry| entry.id).collect();
+        std::mem::take(&mut running.pending)
+    }
+
+    /// Runs observers of `entity` and then global observers
+    /// If any of them asks to propagate, repeats for the [`Parent`]
+    /// Propagation stops at already visited entities, so cycles
+    /// of parents are visited once
+    #[tracing::instrument(skip_all)]
+    pub fn trigger(&mut self, event: &E, mut entity: Entity, world: &mut World) {
+        let mut visited = HashSet::new();
+        loop {
+            visited.insert(entity);
+            let mut propagate = false;
+            let (targeted, global): (Vec<_>, Vec<_>) = self
+                .entries
+                .iter_mut()
+                .filter(|entry| entry.target.is_none() || entry.target == Some(entity))
+                .partition(|entry| entry.target.is_some());
+            for entry in targeted.into_iter().chain(global) {
+                let trigger = Trigger {
+                    event,
+                    entity,
+                    propagate: &mut propagate,
+                };
+                entry.observer.run(trigger, world);
+                world.flush();
+                entry.observer.apply_deferred(world);
+            }
+
+            match world.get_component::<Parent>(entity) {
+                Ok(parent) if propagate && !visited.contains(&parent.0) => entity = parent.0,
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        commands::Commands,
+        query::Query,
+        resources::{ResMut, Resource},
+    };
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Explode(u32);
+    impl Event for Explode {}
+
+    #[derive(Debug, Default)]
+    struct Log(Vec<(&'static str, Entity)>);
+    impl Resource for Log {}
+
+    #[test]
+    fn observer_order_and_propagation() {
+        let mut world = World::default();
+        world.add_resource(Log::default());
+
+        let root = world.create();
+        let child = world.create();
+        world.add_component(child, Parent(root)).unwrap();
+        let other = world.create();
+
+        world.observe(|trigger: Trigger<Explode>, mut log: ResMut<Log>| {
+            log.0.push(("global", trigger.entity()));
+        });
+        world.observe_entity(
+            child,
+            |mut trigger: Trigger<Explode>, mut log: ResMut<Log>| {
+                log.0.push(("child", trigger.entity()));
+                trigger.propagate(trigger.event().0 > 1);
+            },
+        );
+        world.observe_entity(root, |trigger: Trigger<Explode>, mut log: ResMut<Log>| {
+            log.0.push(("root", trigger.entity()));
+        });
+
+        world.trigger(Explode(1), child);
+        world.trigger(Explode(2), child);
+        world.trigger(Explode(2), other);
+
+        assert_eq!(
+            world.get_resource::<Log>().unwrap().0,
+            [
+                ("child", child),
+                ("global", child),
+                ("child", child),
+                ("global", child),
+                ("root", root),
+                ("global", root),
+                ("global", other),
+            ]
+        );
+    }
+
+    #[test]
+    fn observer_commands_and_remove() {
+        #[derive(Debug)]
+        struct Health(u32);
+        impl_component!(Health);
+
+        let mut world = World::default();
+        let entity = world.create();
+        world.add_component(entity, Health(10)).unwrap();
+
+        let id = world.observe(
+            |trigger: Trigger<Explode>,
+             query: Query<(Entity, &Health), 1>,
+             mut commands: Commands| {
+                for (entity, health) in query.iter() {
+                    if health.0 <= trigger.event().0 {
+                        commands.despawn(entity);
+                    }
+                }
+            },
+        );
+
+        world.trigger(Explode(5), entity);
+        assert!(world.get_component::<Health>(entity).is_ok());
+        world.trigger(Explode(10), entity);
+        assert!(world.get_component::<Health>(entity).is_err());
+
+        assert!(world.remove_observer::<Explode>(id));
+        assert!(!world.remove_observer::<Explode>(id));
+    }
+
+    #[test]
+    fn observer_parent_cycle() {
+        let mut world = World::default();
+        world.add_resource(Log::default());
+
+        let a = world.create();
+        let b = world.create();
+        world.add_component(a, Parent(b)).unwrap();
+        world.add_component(b, Parent(a)).unwrap();
+
+        world.observe(|mut trigger: Trigger<Explode>, mut log: ResMut<Log>| {
+            log.0.push(("global", trigger.entity()));
+            trigger.propagate(true);
+        });
+        world.trigger(Explode(1), a);
+
+        assert_eq!(
+            world.get_resource::<Log>().unwrap().0,
+            [("global", a), ("global", b)]
+        );
+    }
+
+    #[test]
+    fn observer_nested_triggers() {
+        #[derive(Debug, Default)]
+        struct Chain(Vec<(&'static str, u32)>);
+        impl Resource for Chain {}
+
+        let mut world = World::default();
+        world.add_resource(Chain::default());
+        let entity = world.create();
+
+        world.observe(
+            |trigger: Trigger<Explode>, mut chain: ResMut<Chain>, mut commands: Commands| {
+                let n = trigger.event().0;
+                chain.0.push(("first", n));
+                if n > 0 {
+                    commands.trigger(Explode(n - 1), trigger.entity());
+                    commands.trigger(Explode(n - 1), trigger.entity());
+                }
+            },
+        );
+        world.observe(|trigger: Trigger<Explode>, mut chain: ResMut<Chain>| {
+            chain.0.push(("second", trigger.event().0));
+        });
+        world.trigger(Explode(1), entity);
+
+        assert_eq!(
+            world.get_resource::<Chain>().unwrap().0,
+            [
+                ("first", 1),
+                ("second", 1),
+                ("first", 0),
+                ("second", 0),
+                ("first", 0),
+                ("second", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn observer_remove_while_running_and_panic() {
+        #[derive(Debug)]
+        struct OnceId(SystemId);
+        impl Resource for OnceId {}
+
+        let mut world = World::default();
+        world.add_resource(Log::default());
+        let entity = world.create();
+
+        let once = world.observe(
+            |trigger: Trigger<Explode>, mut log: ResMut<Log>, mut commands: Commands| {
+                log.0.push(("once", trigger.entity()));
+                commands.add(|world| {
+                    let id = world.get_resource::<OnceId>().unwrap().0;
+                    assert!(world.remove_observer::<Explode>(id));
+                    assert!(!world.remove_observer::<Explode>(id));
+                });
+            },
+        );
+        world.add_resource(OnceId(once));
+        let panics = world.observe(|trigger: Trigger<Explode>| {
+            assert!(trigger.event().0 > 0);
+        });
+
+        world.trigger(Explode(1), entity);
+        world.trigger(Explode(1), entity);
+        assert_eq!(world.get_resource::<Log>().unwrap().0, [("once", entity)]);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            world.trigger(Explode(0), entity);
+        }));
+        assert!(result.is_err());
+        // observers are put back after the panic
+        assert!(world.remove_observer::<Explode>(panics));
+        assert!(!world.remove_observer::<Explode>(once));
+    }
+}
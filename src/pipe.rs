@@ -0,0 +1,368 @@
+use std::marker::PhantomData;
+
+use crate::{
+    access::Access,
+    system::{
+        FunctionSystem, IntoSystemResult, System, SystemError, SystemParameter,
+        SystemParameterCache, SystemParameterFetch, SystemParameterFunction, SystemParameterItem,
+    },
+    world::{UnsafeWorldCell, World},
+};
+
+/// Input of a system which is passed from the previous system in a pipe
+#[derive(Debug)]
+pub struct In<T>(pub T);
+
+/// Function with [`In`] as the first parameter
+pub trait SystemInputFunction<Input, Parameter: SystemParameter>: 'static {
+    type Output;
+
+    fn run(&mut self, input: Input, params: SystemParameterItem<Parameter>) -> Self::Output;
+}
+
+macro_rules! impl_system_input_func {
+    ($($t:ident),*) => {
+        impl<F, R, T, $($t),*> SystemInputFunction<T, ($($t, )*)> for F
+        where
+            F: FnMut(In<T>, $($t),*) -> R + 'static,
+            F: FnMut(In<T>, $(SystemParameterItem<$t>),*) -> R + 'static,
+            $($t: SystemParameter),*,
+        {
+            type Output = R;
+
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            fn run(&mut self, input: T, params: SystemParameterItem<($($t, )*)>) -> R {
+                fn call<R, T, $($t),*>(
+                    mut f: impl FnMut(In<T>, $($t),*) -> R,
+                    input: In<T>,
+                    $($t: $t),*
+                ) -> R {
+                    f(input, $($t),*)
+                }
+                let ($($t, )*) = params;
+                call(self, In(input), $($t),*)
+            }
+        }
+    };
+}
+
+impl<F, R, T> SystemInputFunction<T, ()> for F
+where
+    F: FnMut(In<T>) -> R + 'static,
+{
+    type Output = R;
+
+    fn run(&mut self, input: T, _: ()) -> R {
+        self(In(input))
+    }
+}
+
+impl_system_input_func!(P1);
+impl_system_input_func!(P1, P2);
+impl_system_input_func!(P1, P2, P3);
+impl_system_input_func!(P1, P2, P3, P4);
+impl_system_input_func!(P1, P2, P3, P4, P5);
+impl_system_input_func!(P1, P2, P3, P4, P5, P6);
+
+/// System which returns a value
+pub trait OutputSystem: Send + 'static {
+    type Output;
+
+    /// # Safety
+    /// Same as [`System::run_unsafe`]
+    unsafe fn run_with_output(&mut self, world: UnsafeWorldCell) -> Self::Output;
+
+    fn access(&self) -> &Access;
+
+    fn apply_deferred(&mut self, world: &mut World);
+
+    fn initialize(&mut self, world: &mut World);
+}
+
+impl<S, P> OutputSystem for FunctionSystem<S, P>
+where
+    S: SystemParameterFunction<P> + Send + 'static,
+    P: SystemParameter + 'static,
+{
+    type Output = S::Output;
+
+    unsafe fn run_with_output(&mut self, world: UnsafeWorldCell) -> Self::Output {
+        self.run_function_unsafe(world)
+    }
+
+    fn access(&self) -> &Access {
+        FunctionSystem::access(self)
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        FunctionSystem::apply_deferred(self, world)
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        FunctionSystem::initialize(self, world)
+    }
+}
+
+/// System which takes an input
+pub trait InputSystem<T>: Send + 'static {
+    type Output;
+
+    /// # Safety
+    /// Same as [`System::run_unsafe`]
+    unsafe fn run_with_input(&mut self, input: T, world: UnsafeWorldCell) -> Self::Output;
+
+    fn access(&self) -> &Access;
+
+    fn apply_deferred(&mut self, world: &mut World);
+
+    fn initialize(&mut self, world: &mut World);
+}
+
+pub struct InputFunctionSystem<S, T, Params: SystemParameter> {
+    system: S,
+    cache: <<Params as SystemParameter>::Fetch as SystemParameterFetch>::Cache,
+    access: Access,
+    is_initialized: bool,
+    params: PhantomData<fn(T) -> Params>,
+}
+
+impl<S, T, P> InputSystem<T> for InputFunctionSystem<S, T, P>
+where
+    S: SystemInputFunction<T, P> + Send + 'static,
+    T: 'static,
+    P: SystemParameter + 'static,
+{
+    type Output = S::Output;
+
+    #[tracing::instrument(skip_all)]
+    unsafe fn run_with_input(&mut self, input: T, world: UnsafeWorldCell) -> Self::Output {
+        let world = world.with_new_change_tick();
+        let params = P::Fetch::fetch(world, &mut self.cache);
+        self.system.run(input, params)
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        P::Fetch::apply(&mut self.cache, world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        if !self.is_initialized {
+            self.cache = P::Fetch::init_cache(world, std::any::type_name::<S>());
+            self.is_initialized = true;
+        }
+    }
+}
+
+pub trait IntoInputSystem<T, Marker> {
+    type System: InputSystem<T>;
+
+    fn into_input_system(self) -> Self::System;
+}
+
+impl<S, T, P> IntoInputSystem<T, P> for S
+where
+    S: SystemInputFunction<T, P> + Send + 'static,
+    T: 'static,
+    P: SystemParameter + 'static,
+{
+    type System = InputFunctionSystem<S, T, P>;
+
+    fn into_input_system(self) -> Self::System {
+        let mut access = Access::default();
+        P::Fetch::access(&mut access);
+        InputFunctionSystem {
+            system: self,
+            cache: <<P as SystemParameter>::Fetch as SystemParameterFetch>::Cache::empty(),
+            access,
+            is_initialized: false,
+            params: PhantomData,
+        }
+    }
+}
+
+/// Marker for [`IntoOutputSystem`] implementation of [`PipeSystem`]
+pub struct PipeMarker;
+
+pub trait IntoOutputSystem<Marker>: Sized {
+    type System: OutputSystem;
+
+    fn into_output_system(self) -> Self::System;
+
+    /// Passes output of `self` to `system` as [`In`] parameter
+    fn pipe<B, M>(self, system: B) -> PipeSystem<Self::System, B::System>
+    where
+        B: IntoInputSystem<<Self::System as OutputSystem>::Output, M>,
+    {
+        let a = self.into_output_system();
+        let b = system.into_input_system();
+        let mut access = a.access().clone();
+        access.extend(b.access());
+        PipeSystem { a, b, access }
+    }
+}
+
+impl<S, P> IntoOutputSystem<P> for S
+where
+    S: SystemParameterFunction<P> + Send + 'static,
+    P: SystemParameter + 'static,
+{
+    type System = FunctionSystem<S, P>;
+
+    fn into_output_system(self) -> Self::System {
+        FunctionSystem::new(self)
+    }
+}
+
+impl<A, B> IntoOutputSystem<PipeMarker> for PipeSystem<A, B>
+where
+    A: OutputSystem,
+    B: InputSystem<A::Output>,
+{
+    type System = Self;
+
+    fn into_output_system(self) -> Self::System {
+        self
+    }
+}
+
+/// Two systems run one after another with output of the first
+/// one passed to the second one
+pub struct PipeSystem<A, B> {
+    a: A,
+    b: B,
+    access: Access,
+}
+
+impl<A, B> OutputSystem for PipeSystem<A, B>
+where
+    A: OutputSystem,
+    B: InputSystem<A::Output>,
+{
+    type Output = B::Output;
+
+    unsafe fn run_with_output(&mut self, world: UnsafeWorldCell) -> Self::Output {
+        let output = self.a.run_with_output(world);
+        self.b.run_with_input(output, world)
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        self.a.apply_deferred(world);
+        self.b.apply_deferred(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.a.initialize(world);
+        self.b.initialize(world);
+    }
+}
+
+impl<A, B> System for PipeSystem<A, B>
+where
+    A: OutputSystem,
+    B: InputSystem<A::Output>,
+    B::Output: IntoSystemResult,
+{
+    #[tracing::instrument(skip_all)]
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell) -> Result<(), SystemError> {
+        self.run_with_output(world).into_system_result()
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        OutputSystem::apply_deferred(self, world)
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        OutputSystem::initialize(self, world)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        resources::{Res, ResMut, Resource},
+        system::Systems,
+    };
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Text(&'static str);
+    impl Resource for Text {}
+
+    #[derive(Debug, Default)]
+    struct Numbers(Vec<u32>);
+    impl Resource for Numbers {}
+
+    fn parse(text: Res<Text>) -> Result<u32, String> {
+        text.0.parse().map_err(|_| "not a number".to_string())
+    }
+
+    fn apply(
+        In(number): In<Result<u32, String>>,
+        mut numbers: ResMut<Numbers>,
+    ) -> Result<(), String> {
+        numbers.0.push(number?);
+        Ok(())
+    }
+
+    #[test]
+    fn pipe_systems() {
+        let mut world = World::default();
+        world.add_resource(Text("7"));
+        world.add_resource(Numbers::default());
+
+        let mut systems = Systems::default();
+        systems.add_system(parse.pipe(apply));
+        systems.add_system(parse.pipe(|In(n): In<Result<u32, String>>| n.map(|_| ())));
+        systems.run(&mut world);
+
+        world.get_resource_mut::<Text>().unwrap().0 = "seven";
+        systems.run(&mut world);
+
+        assert_eq!(world.get_resource::<Numbers>().unwrap().0, [7]);
+    }
+
+    #[test]
+    fn pipe_access_and_chain() {
+        fn double(In(n): In<u32>) -> u32 {
+            n * 2
+        }
+        fn store(In(n): In<u32>, mut numbers: ResMut<Numbers>) {
+            numbers.0.push(n);
+        }
+
+        let mut world = World::default();
+        world.add_resource(Text("21"));
+        world.add_resource(Numbers::default());
+
+        let mut system = (|text: Res<Text>| text.0.parse::<u32>().unwrap())
+            .pipe(double)
+            .pipe(store);
+
+        let mut read_text = Access::default();
+        read_text.read_resource(crate::utils::types::TypeId::of::<Text>());
+        let mut read_numbers = Access::default();
+        read_numbers.read_resource(crate::utils::types::TypeId::of::<Numbers>());
+        assert!(System::access(&system).is_compatible(&read_text));
+        assert!(!System::access(&system).is_compatible(&read_numbers));
+
+        System::run(&mut system, &mut world).unwrap();
+        assert_eq!(world.get_resource::<Numbers>().unwrap().0, [42]);
+    }
+}
@@ -3,9 +3,10 @@ use std::marker::PhantomData;
 use bumpalo::Bump;
 
 use crate::{
+    access::Access,
     component::ComponentTuple,
     system::{SystemParameter, SystemParameterCache, SystemParameterFetch},
-    world::World,
+    world::{UnsafeWorldCell, World},
 };
 
 pub struct Query<'world, 'cache, T, const L: usize>
@@ -24,6 +25,22 @@ where
     pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
         self.world.query_with_cache::<T, L>(self.cache)
     }
+
+    /// Runs `f` for every matched entity on the rayon thread pool
+    /// Matched tables are split into batches of `batch_size` lines
+    pub fn par_for_each(&self, batch_size: usize, f: impl Fn(T) + Send + Sync)
+    where
+        T: Send,
+    {
+        self.world
+            .par_for_each_with_cache::<T, L>(self.cache, batch_size, f)
+    }
+
+    /// Iterates over matched tables returning components
+    /// of each table as slices
+    pub fn iter_chunks(&self) -> impl Iterator<Item = T::Chunk<'_>> + '_ {
+        self.world.query_chunks_with_cache::<T, L>(self.cache)
+    }
 }
 
 impl<'world, 'cache, T, const L: usize> SystemParameter for Query<'world, 'cache, T, L>
@@ -48,16 +65,20 @@ where
     type Item<'world, 'cache> = Query<'world, 'cache, T, L>;
     type Cache = QueryCache;
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        cache: &'cache Self::Cache,
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache> {
         Self::Item {
-            world,
+            world: world.world(),
             cache,
             phantom: PhantomData,
         }
     }
+
+    fn access(access: &mut Access) {
+        T::access(access);
+    }
 }
 
 pub struct QueryCache {
@@ -198,4 +219,79 @@ mod test {
 
         systems.run(&mut ecs);
     }
+
+    #[test]
+    fn query_chunks_in_ecs() {
+        let mut ecs = World::default();
+
+        let entity = ecs.create();
+        ecs.add_component(entity, 1u8).unwrap();
+        ecs.add_component(entity, 2u16).unwrap();
+
+        let entity2 = ecs.create();
+        ecs.add_component(entity2, 3u8).unwrap();
+        ecs.add_component(entity2, 4u16).unwrap();
+
+        let entity3 = ecs.create();
+        ecs.add_component(entity3, 5u8).unwrap();
+        ecs.add_component(entity3, 6u16).unwrap();
+        ecs.add_component(entity3, 7u32).unwrap();
+
+        fn query_u8_u16_mutate(query: Query<(Entity, &mut u8, &u16), 2>) {
+            for (entities, u8s, u16s) in query.iter_chunks() {
+                assert_eq!(entities.len(), u8s.len());
+                assert_eq!(entities.len(), u16s.len());
+                for (a, b) in u8s.iter_mut().zip(u16s.iter()) {
+                    *a += *b as u8;
+                }
+            }
+        }
+
+        fn query_u8_check(query: Query<(&u8,), 1>) {
+            let mut chunks = query.iter_chunks().collect::<Vec<_>>();
+            chunks.sort_unstable();
+            let expected = [(&[3, 7][..],), (&[11][..],)];
+            assert_eq!(chunks, expected);
+        }
+
+        let mut systems = Systems::default();
+
+        systems.add_system(query_u8_u16_mutate);
+        systems.add_system(query_u8_check);
+
+        systems.run(&mut ecs);
+    }
+
+    #[test]
+    fn query_par_for_each_in_ecs() {
+        let mut ecs = World::default();
+
+        for i in 0..1000 {
+            let entity = ecs.create();
+            ecs.add_component(entity, i as u32).unwrap();
+            if i % 2 == 0 {
+                ecs.add_component(entity, 1u8).unwrap();
+            }
+        }
+
+        fn query_u32_mutate(query: Query<(&mut u32,), 1>) {
+            query.par_for_each(64, |(a,)| {
+                *a *= 2;
+            });
+        }
+
+        fn query_u32_check(query: Query<(&u32,), 1>) {
+            let mut results = query.iter().map(|(a,)| *a).collect::<Vec<_>>();
+            results.sort_unstable();
+            let expected = (0..1000).map(|i| i * 2).collect::<Vec<_>>();
+            assert_eq!(results, expected);
+        }
+
+        let mut systems = Systems::default();
+
+        systems.add_system(query_u32_mutate);
+        systems.add_system(query_u32_check);
+
+        systems.run(&mut ecs);
+    }
 }
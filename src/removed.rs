@@ -0,0 +1,134 @@
+use std::marker::PhantomData;
+
+use crate::{
+    access::Access,
+    component::Component,
+    entity::Entity,
+    events::{Event, EventCursor, Events},
+    system::{SystemParameter, SystemParameterFetch},
+    world::{UnsafeWorldCell, World},
+};
+
+/// Entity which lost a component or was despawned with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Removed(pub Entity);
+
+impl Event for Removed {}
+
+/// Entities which lost component `C` since the last run of the system
+/// Removals are logged only for components with at least one reader
+#[derive(Debug)]
+pub struct RemovedComponents<'a, C: Component> {
+    removed: &'a Events<Removed>,
+    /// Number of removals the system had read before this run
+    read: usize,
+    phantom: PhantomData<C>,
+}
+
+impl<C: Component> RemovedComponents<'_, C> {
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.removed.since(self.read).map(|removed| removed.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+}
+
+impl<C: Component> SystemParameter for RemovedComponents<'_, C> {
+    type Fetch = RemovedComponentsFetch<C>;
+}
+
+#[derive(Debug)]
+pub struct RemovedComponentsFetch<C: Component> {
+    phantom: PhantomData<C>,
+}
+
+impl<C: Component> SystemParameterFetch for RemovedComponentsFetch<C> {
+    type Item<'world, 'cache> = RemovedComponents<'world, C>;
+    type Cache = EventCursor;
+
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
+    ) -> Self::Item<'world, 'cache> {
+        let removed = world
+            .removed_components(C::ID)
+            .expect("removals of the component should be tracked");
+        let read = cache.advance(removed);
+        Self::Item {
+            removed,
+            read,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Removal logs are written only with exclusive access to the world
+    fn access(_: &mut Access) {}
+
+    /// Starts logging removals of the component
+    fn init_cache(world: &mut World, _: &'static str) -> Self::Cache {
+        world.track_removed_components(C::ID);
+        Self::Cache::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        impl_component,
+        resources::{ResMut, Resource},
+        system::Systems,
+        utils::types::TypeId,
+    };
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Mesh(u32);
+    impl_component!(Mesh);
+
+    #[derive(Debug, Default)]
+    struct Released(Vec<Entity>);
+    impl Resource for Released {}
+
+    #[test]
+    fn removed_components() {
+        fn release(removed: RemovedComponents<Mesh>, mut released: ResMut<Released>) {
+            released.0.extend(removed.iter());
+        }
+
+        let mut world = World::default();
+        world.add_resource(Released::default());
+        let mut systems = Systems::default();
+        systems.add_system(release);
+
+        let a = world.create();
+        world.add_component(a, Mesh(0)).unwrap();
+        world.add_component(a, 1u8).unwrap();
+        let b = world.create();
+        world.add_component(b, Mesh(1)).unwrap();
+        let c = world.create();
+        world.add_component(c, 1u8).unwrap();
+
+        let d = world.create();
+        world.add_component(d, Mesh(2)).unwrap();
+
+        // removals before the first run are not tracked yet
+        world.remove_component::<Mesh>(d).unwrap();
+        systems.run(&mut world);
+
+        assert_eq!(world.get_component::<Mesh>(b).unwrap().0, 1);
+        world.remove_component::<Mesh>(a).unwrap();
+        world.despawn(b).unwrap();
+        world.despawn(c).unwrap();
+        systems.run(&mut world);
+        systems.run(&mut world);
+
+        assert_eq!(world.get_resource::<Released>().unwrap().0, [a, b]);
+    }
+}
@@ -1,10 +1,19 @@
-use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
+use std::{
+    cell::UnsafeCell,
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    thread::ThreadId,
+};
 
 use crate::{
+    access::Access,
     blobvec::BlobVec,
-    system::{SystemParameter, SystemParameterFetch},
+    system::{SystemParameter, SystemParameterCache, SystemParameterFetch},
     utils::types::{TypeId, TypeInfo},
-    world::World,
+    world::{UnsafeWorldCell, World},
 };
 
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
@@ -15,23 +24,59 @@ pub enum Error {
     GetNonExisting(&'static str),
 }
 
-pub trait Resource: Debug + 'static {}
+pub trait Resource: Debug + Send + Sync + 'static {}
+
+/// Creates resource from the world with [`World::init_resource`]
+pub trait FromWorld {
+    fn from_world(world: &mut World) -> Self;
+}
 
+impl<T: Default> FromWorld for T {
+    fn from_world(_world: &mut World) -> Self {
+        T::default()
+    }
+}
+
+/// Shared reference to the resource
+///
+/// # Panics
+/// Fetching panics if the resource does not exist, use
+/// `Option<Res<T>>` for resources which can be missing
 #[derive(Debug)]
 pub struct Res<'world, T>
 where
     T: Resource,
 {
-    world: &'world World,
-    phantom: PhantomData<T>,
+    value: &'world T,
+    ticks: &'world ResourceTicks,
+    last_run: u32,
+    this_run: u32,
 }
 
 impl<T> Res<'_, T>
 where
     T: Resource,
 {
-    pub fn get(&self) -> Result<&T, crate::world::Error> {
-        self.world.get_resource()
+    /// True if the resource was added since the last run of the system
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_run, self.this_run)
+    }
+
+    /// True if the resource was added or mutated since the last run
+    /// of the system
+    pub fn is_changed(&self) -> bool {
+        self.ticks.is_changed(self.last_run, self.this_run)
+    }
+}
+
+impl<T> Deref for Res<'_, T>
+where
+    T: Resource,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
     }
 }
 
@@ -42,6 +87,45 @@ where
     type Fetch = ResFetch<T>;
 }
 
+impl<'a, T> SystemParameter for Option<Res<'a, T>>
+where
+    T: Resource,
+{
+    type Fetch = OptionResFetch<T>;
+}
+
+/// Name of the system used in the panic message
+/// if the resource does not exist and the change tick
+/// of the last fetch
+#[derive(Debug, Default)]
+pub struct ResCache {
+    system: &'static str,
+    last_run: u32,
+}
+
+impl ResCache {
+    /// Returns the tick of the last run and the tick of this run
+    /// The system advances the change tick of the cell before fetching
+    fn advance(&mut self, world: UnsafeWorldCell) -> (u32, u32) {
+        let this_run = world.change_tick();
+        (std::mem::replace(&mut self.last_run, this_run), this_run)
+    }
+}
+
+impl SystemParameterCache for ResCache {
+    fn empty() -> Self {
+        Self::default()
+    }
+}
+
+fn missing_resource<T: 'static>(cache: &ResCache) -> ! {
+    panic!(
+        "Resource {} requested by system {} does not exist",
+        std::any::type_name::<T>(),
+        cache.system
+    )
+}
+
 #[derive(Debug)]
 pub struct ResFetch<T>
 where
@@ -55,34 +139,123 @@ where
     T: Resource,
 {
     type Item<'world, 'cache> = Res<'world, T>;
-    type Cache = ();
+    type Cache = ResCache;
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        _: &'cache Self::Cache,
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache> {
-        Self::Item {
-            world,
-            phantom: PhantomData,
+        let (last_run, this_run) = cache.advance(world);
+        match world.get_resource_with_ticks() {
+            Ok((value, ticks)) => Self::Item {
+                value,
+                ticks,
+                last_run,
+                this_run,
+            },
+            Err(_) => missing_resource::<T>(cache),
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.read_resource(TypeId::of::<T>());
+    }
+
+    fn init_cache(_world: &mut World, system: &'static str) -> Self::Cache {
+        ResCache {
+            system,
+            last_run: 0,
         }
     }
 }
 
 #[derive(Debug)]
-pub struct ResMut<'world, T>
+pub struct OptionResFetch<T>
 where
     T: Resource,
 {
-    world: &'world mut World,
     phantom: PhantomData<T>,
 }
 
+impl<T> SystemParameterFetch for OptionResFetch<T>
+where
+    T: Resource,
+{
+    type Item<'world, 'cache> = Option<Res<'world, T>>;
+    type Cache = ResCache;
+
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
+    ) -> Self::Item<'world, 'cache> {
+        let (last_run, this_run) = cache.advance(world);
+        world
+            .get_resource_with_ticks()
+            .ok()
+            .map(|(value, ticks)| Res {
+                value,
+                ticks,
+                last_run,
+                this_run,
+            })
+    }
+
+    fn access(access: &mut Access) {
+        access.read_resource(TypeId::of::<T>());
+    }
+}
+
+/// Mutable reference to the resource
+///
+/// # Panics
+/// Fetching panics if the resource does not exist, use
+/// `Option<ResMut<T>>` for resources which can be missing
+#[derive(Debug)]
+pub struct ResMut<'world, T>
+where
+    T: Resource,
+{
+    value: &'world mut T,
+    ticks: &'world mut ResourceTicks,
+    last_run: u32,
+    this_run: u32,
+}
+
 impl<T> ResMut<'_, T>
 where
     T: Resource,
 {
-    pub fn get_mut(&mut self) -> Result<&mut T, crate::world::Error> {
-        self.world.get_resource_mut()
+    /// True if the resource was added since the last run of the system
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_run, self.this_run)
+    }
+
+    /// True if the resource was added or mutated since the last run
+    /// of the system
+    pub fn is_changed(&self) -> bool {
+        self.ticks.is_changed(self.last_run, self.this_run)
+    }
+}
+
+impl<T> Deref for ResMut<'_, T>
+where
+    T: Resource,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> DerefMut for ResMut<'_, T>
+where
+    T: Resource,
+{
+    /// Marks the resource as changed
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ticks.changed = self.this_run;
+        self.value
     }
 }
 
@@ -93,6 +266,13 @@ where
     type Fetch = ResMutFetch<T>;
 }
 
+impl<'a, T> SystemParameter for Option<ResMut<'a, T>>
+where
+    T: Resource,
+{
+    type Fetch = OptionResMutFetch<T>;
+}
+
 #[derive(Debug)]
 pub struct ResMutFetch<T>
 where
@@ -106,26 +286,236 @@ where
     T: Resource,
 {
     type Item<'world, 'cache> = ResMut<'world, T>;
-    type Cache = ();
+    type Cache = ResCache;
+
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
+    ) -> Self::Item<'world, 'cache> {
+        let (last_run, this_run) = cache.advance(world);
+        match world.get_resource_mut_with_ticks() {
+            Ok((value, ticks)) => Self::Item {
+                value,
+                ticks,
+                last_run,
+                this_run,
+            },
+            Err(_) => missing_resource::<T>(cache),
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.write_resource(TypeId::of::<T>());
+    }
+
+    fn init_cache(_world: &mut World, system: &'static str) -> Self::Cache {
+        ResCache {
+            system,
+            last_run: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OptionResMutFetch<T>
+where
+    T: Resource,
+{
+    phantom: PhantomData<T>,
+}
+
+impl<T> SystemParameterFetch for OptionResMutFetch<T>
+where
+    T: Resource,
+{
+    type Item<'world, 'cache> = Option<ResMut<'world, T>>;
+    type Cache = ResCache;
+
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
+    ) -> Self::Item<'world, 'cache> {
+        let (last_run, this_run) = cache.advance(world);
+        world
+            .get_resource_mut_with_ticks()
+            .ok()
+            .map(|(value, ticks)| ResMut {
+                value,
+                ticks,
+                last_run,
+                this_run,
+            })
+    }
+
+    fn access(access: &mut Access) {
+        access.write_resource(TypeId::of::<T>());
+    }
+}
+
+/// Shared reference to the non send resource
+/// Systems with it have to run on the thread which created the world
+///
+/// # Panics
+/// Fetching panics if the resource does not exist
+#[derive(Debug)]
+pub struct NonSend<'world, T: 'static> {
+    value: &'world T,
+}
+
+impl<T: 'static> Deref for NonSend<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<'a, T: 'static> SystemParameter for NonSend<'a, T> {
+    type Fetch = NonSendFetch<T>;
+}
+
+#[derive(Debug)]
+pub struct NonSendFetch<T: 'static> {
+    phantom: PhantomData<T>,
+}
+
+impl<T: 'static> SystemParameterFetch for NonSendFetch<T> {
+    type Item<'world, 'cache> = NonSend<'world, T>;
+    type Cache = ResCache;
+
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
+    ) -> Self::Item<'world, 'cache> {
+        match world.get_non_send_resource() {
+            Ok(value) => Self::Item { value },
+            Err(_) => missing_resource::<T>(cache),
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.read_resource(TypeId::of::<T>());
+        access.set_non_send();
+    }
+
+    fn init_cache(_world: &mut World, system: &'static str) -> Self::Cache {
+        ResCache {
+            system,
+            last_run: 0,
+        }
+    }
+}
+
+/// Mutable reference to the non send resource
+/// Systems with it have to run on the thread which created the world
+///
+/// # Panics
+/// Fetching panics if the resource does not exist
+#[derive(Debug)]
+pub struct NonSendMut<'world, T: 'static> {
+    value: &'world mut T,
+}
+
+impl<T: 'static> Deref for NonSendMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T: 'static> DerefMut for NonSendMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+    }
+}
+
+impl<'a, T: 'static> SystemParameter for NonSendMut<'a, T> {
+    type Fetch = NonSendMutFetch<T>;
+}
+
+#[derive(Debug)]
+pub struct NonSendMutFetch<T: 'static> {
+    phantom: PhantomData<T>,
+}
+
+impl<T: 'static> SystemParameterFetch for NonSendMutFetch<T> {
+    type Item<'world, 'cache> = NonSendMut<'world, T>;
+    type Cache = ResCache;
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        _: &'cache Self::Cache,
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache> {
-        Self::Item {
-            world,
-            phantom: PhantomData,
+        match world.get_non_send_resource_mut() {
+            Ok(value) => Self::Item { value },
+            Err(_) => missing_resource::<T>(cache),
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.write_resource(TypeId::of::<T>());
+        access.set_non_send();
+    }
+
+    fn init_cache(_world: &mut World, system: &'static str) -> Self::Cache {
+        ResCache {
+            system,
+            last_run: 0,
+        }
+    }
+}
+
+/// Change ticks of the resource
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceTicks {
+    /// Change tick of the world when the resource was added
+    pub added: u32,
+    /// Change tick of the world when the resource was last mutated
+    pub changed: u32,
+}
+
+impl ResourceTicks {
+    pub fn new(tick: u32) -> Self {
+        Self {
+            added: tick,
+            changed: tick,
         }
     }
+
+    /// True if the resource was added after `last_run` and not after `this_run`
+    /// Ticks are compared relative to `this_run`, so they can wrap
+    pub fn is_added(&self, last_run: u32, this_run: u32) -> bool {
+        is_newer(self.added, last_run, this_run)
+    }
+
+    /// True if the resource was mutated after `last_run` and not after `this_run`
+    pub fn is_changed(&self, last_run: u32, this_run: u32) -> bool {
+        is_newer(self.changed, last_run, this_run)
+    }
+}
+
+/// True if `tick` is more recent than `last_run` as seen from `this_run`
+fn is_newer(tick: u32, last_run: u32, this_run: u32) -> bool {
+    this_run.wrapping_sub(tick) < this_run.wrapping_sub(last_run)
+}
+
+#[derive(Debug)]
+struct ResourceColumn {
+    column: BlobVec,
+    /// Mutated through shared reference by [`ResMut`]
+    ticks: UnsafeCell<ResourceTicks>,
 }
 
 #[derive(Debug, Default)]
 pub struct Resources {
-    columns: HashMap<TypeId, BlobVec>,
+    columns: HashMap<TypeId, ResourceColumn>,
 }
 
 impl Resources {
-    pub fn add<T: Resource>(&mut self, resource: T) {
+    /// Adds the resource or replaces existing one
+    /// Replaced resource keeps its added tick
+    pub fn add<T: 'static>(&mut self, resource: T, tick: u32) {
         let type_info = TypeInfo::new::<T>();
         match self.columns.get_mut(&type_info.id) {
             Some(column) => {
@@ -134,21 +524,30 @@ impl Resources {
                 // with new one.
                 // Old instance will be dropped here.
                 unsafe {
-                    let _ = column.swap(0, resource);
+                    let _ = column.column.swap(0, resource);
                 }
+                column.ticks.get_mut().changed = tick;
             }
-            None => {
-                let mut blob = BlobVec::new(type_info.layout, type_info.drop);
+            None => self.insert(resource, ResourceTicks::new(tick)),
+        }
+    }
 
-                // Safe becaus blob has the correct type
-                unsafe { blob.push(resource) };
+    /// Adds the resource with given ticks
+    /// Existing resource of the same type is dropped
+    pub fn insert<T: 'static>(&mut self, resource: T, ticks: ResourceTicks) {
+        let type_info = TypeInfo::new::<T>();
+        let _ = self.remove::<T>();
 
-                self.columns.insert(type_info.id.into(), blob);
-            }
-        }
+        let mut column = BlobVec::new(type_info.layout, type_info.drop);
+        // Safe becaus blob has the correct type
+        unsafe { column.push(resource) };
+
+        let ticks = UnsafeCell::new(ticks);
+        self.columns
+            .insert(type_info.id.into(), ResourceColumn { column, ticks });
     }
 
-    pub fn remove<T: Resource>(&mut self) -> Result<(), Error> {
+    pub fn remove<T: 'static>(&mut self) -> Result<(), Error> {
         let type_info = TypeInfo::new::<T>();
         self.columns
             .remove(&type_info.id)
@@ -158,48 +557,202 @@ impl Resources {
                 // with new one.
                 // Old instance will be dropped here.
                 unsafe {
-                    column.drop_at(0);
+                    column.column.drop_at(0);
                 }
             })
             .ok_or(Error::RemoveNonExisting(type_info.name))
     }
 
-    pub fn get<T: Resource>(&self) -> Result<&T, Error> {
+    /// Removes the resource and returns it with its ticks
+    pub fn take<T: 'static>(&mut self) -> Result<(T, ResourceTicks), Error> {
+        let type_info = TypeInfo::new::<T>();
+        self.columns
+            .remove(&type_info.id)
+            .map(|mut column| {
+                // Safe because column contains a corret type.
+                // Object is moved out, so it is removed
+                // from the column without drop.
+                let resource = unsafe {
+                    let resource = std::ptr::read(column.column.get::<T>(0));
+                    column.column.swap_remove_forget(0);
+                    resource
+                };
+                (resource, column.ticks.into_inner())
+            })
+            .ok_or(Error::RemoveNonExisting(type_info.name))
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.columns.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn get<T: 'static>(&self) -> Result<&T, Error> {
+        self.get_with_ticks().map(|(resource, _)| resource)
+    }
+
+    pub fn get_with_ticks<T: 'static>(&self) -> Result<(&T, &ResourceTicks), Error> {
         let type_info = TypeInfo::new::<T>();
         match self.columns.get(&type_info.id) {
             Some(column) => {
                 // Safe because column contains a corret type
-                Ok(unsafe { column.get::<T>(0) })
+                // and ticks are mutated only through unsafe methods
+                Ok(unsafe { (column.column.get::<T>(0), &*column.ticks.get()) })
             }
             None => Err(Error::GetNonExisting(type_info.name)),
         }
     }
 
-    pub fn get_mut<T: Resource>(&mut self) -> Result<&mut T, Error> {
+    /// Does not mark the resource as changed
+    pub fn get_mut<T: 'static>(&mut self) -> Result<&mut T, Error> {
+        self.get_mut_with_ticks().map(|(resource, _)| resource)
+    }
+
+    pub fn get_mut_with_ticks<T: 'static>(
+        &mut self,
+    ) -> Result<(&mut T, &mut ResourceTicks), Error> {
         let type_info = TypeInfo::new::<T>();
         match self.columns.get_mut(&type_info.id) {
             Some(column) => {
                 // Safe because column contains a corret type
-                Ok(unsafe { column.get_mut::<T>(0) })
+                Ok((
+                    unsafe { column.column.get_mut::<T>(0) },
+                    column.ticks.get_mut(),
+                ))
+            }
+            None => Err(Error::GetNonExisting(type_info.name)),
+        }
+    }
+
+    /// Does not mark the resource as changed
+    ///
+    /// # Safety
+    /// Save as long as same resource is accessed only once
+    pub unsafe fn get_mut_unchecked<T: 'static>(&self) -> Result<&mut T, Error> {
+        let type_info = TypeInfo::new::<T>();
+        match self.columns.get(&type_info.id) {
+            Some(column) => {
+                // Safe because column contains a corret type
+                Ok(unsafe { column.column.get_mut_unchecked::<T>(0) })
             }
             None => Err(Error::GetNonExisting(type_info.name)),
         }
     }
 
+    /// Does not mark the resource as changed
+    ///
     /// # Safety
     /// Save as long as same resource is accessed only once
-    pub unsafe fn get_mut_unchecked<T: Resource>(&self) -> Result<&mut T, Error> {
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut_with_ticks_unchecked<T: 'static>(
+        &self,
+    ) -> Result<(&mut T, &mut ResourceTicks), Error> {
         let type_info = TypeInfo::new::<T>();
         match self.columns.get(&type_info.id) {
             Some(column) => {
                 // Safe because column contains a corret type
-                Ok(unsafe { column.get_mut_unchecked::<T>(0) })
+                Ok(unsafe {
+                    (
+                        column.column.get_mut_unchecked::<T>(0),
+                        &mut *column.ticks.get(),
+                    )
+                })
             }
             None => Err(Error::GetNonExisting(type_info.name)),
         }
     }
 }
 
+/// Resources which are not [`Send`]
+/// They can be accessed only from the thread
+/// which created the storage
+/// Resources dropped on another thread are leaked
+#[derive(Debug)]
+pub struct NonSendResources {
+    resources: ManuallyDrop<Resources>,
+    thread: ThreadId,
+}
+
+impl Default for NonSendResources {
+    fn default() -> Self {
+        Self {
+            resources: ManuallyDrop::new(Resources::default()),
+            thread: std::thread::current().id(),
+        }
+    }
+}
+
+impl NonSendResources {
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the storage
+    pub fn add<T: 'static>(&mut self, resource: T) {
+        self.validate_thread::<T>();
+        self.resources.add(resource, 0)
+    }
+
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the storage
+    pub fn remove<T: 'static>(&mut self) -> Result<(), Error> {
+        self.validate_thread::<T>();
+        self.resources.remove::<T>()
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.resources.contains::<T>()
+    }
+
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the storage
+    pub fn get<T: 'static>(&self) -> Result<&T, Error> {
+        self.validate_thread::<T>();
+        self.resources.get::<T>()
+    }
+
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the storage
+    pub fn get_mut<T: 'static>(&mut self) -> Result<&mut T, Error> {
+        self.validate_thread::<T>();
+        self.resources.get_mut::<T>()
+    }
+
+    /// # Safety
+    /// Save as long as same resource is accessed only once
+    ///
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the storage
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut_unchecked<T: 'static>(&self) -> Result<&mut T, Error> {
+        self.validate_thread::<T>();
+        self.resources.get_mut_unchecked::<T>()
+    }
+
+    fn is_valid_thread(&self) -> bool {
+        std::thread::current().id() == self.thread
+    }
+
+    fn validate_thread<T: 'static>(&self) {
+        if !self.is_valid_thread() {
+            panic!(
+                "Non send resource {} accessed from thread other than the one which created the world",
+                std::any::type_name::<T>()
+            )
+        }
+    }
+}
+
+impl Drop for NonSendResources {
+    fn drop(&mut self) {
+        if self.is_valid_thread() {
+            // Safe because the resources are not used after drop
+            unsafe { ManuallyDrop::drop(&mut self.resources) }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::system::Systems;
@@ -229,10 +782,10 @@ mod test {
         let mut resources = Resources::default();
 
         let a = A { val: 1 };
-        resources.add(a);
+        resources.add(a, 0);
 
         let b = B { val: 2 };
-        resources.add(b);
+        resources.add(b, 0);
 
         let get_a = resources.get::<A>().unwrap();
         assert_eq!(&a, get_a);
@@ -249,10 +802,10 @@ mod test {
         let mut resources = Resources::default();
 
         let a = A { val: 1 };
-        resources.add(a);
+        resources.add(a, 0);
 
         let b = B { val: 2 };
-        resources.add(b);
+        resources.add(b, 0);
 
         assert!(resources.remove::<A>().is_ok());
 
@@ -271,6 +824,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn resources_take() {
+        #[derive(Debug, PartialEq)]
+        struct Name(String);
+        impl Resource for Name {}
+
+        let mut resources = Resources::default();
+        resources.add(Name("text".to_string()), 1);
+
+        assert_eq!(
+            resources.take::<Name>(),
+            Ok((Name("text".to_string()), ResourceTicks::new(1)))
+        );
+        assert!(!resources.contains::<Name>());
+        assert!(resources.take::<Name>().is_err());
+    }
+
     #[test]
     fn res_system_param() {
         fn test_sys_res(_: Res<A>) {
@@ -278,6 +848,7 @@ mod test {
         }
 
         let mut ecs = World::default();
+        ecs.add_resource(A { val: 1 });
 
         let mut systems = Systems::default();
 
@@ -293,6 +864,7 @@ mod test {
         }
 
         let mut ecs = World::default();
+        ecs.add_resource(A { val: 1 });
 
         let mut systems = Systems::default();
 
@@ -311,21 +883,16 @@ mod test {
         let b = B { val: 2 };
         ecs.add_resource(b);
 
-        fn get_a(res: Res<A>) {
-            let a = res.get().unwrap();
+        fn get_a(a: Res<A>) {
             assert_eq!(a.val, 1);
         }
 
-        fn get_b(res: Res<B>) {
-            let b = res.get().unwrap();
-            assert_eq!(b.val, 2);
+        fn get_b(b: Option<Res<B>>) {
+            assert_eq!(b.unwrap().val, 2);
         }
 
-        fn get_c(res: Res<C>) {
-            assert_eq!(
-                res.get().unwrap_err(),
-                crate::world::Error::Resources(Error::GetNonExisting("kiwi::resources::test::C"))
-            );
+        fn get_c(c: Option<Res<C>>) {
+            assert!(c.is_none());
         }
 
         let mut systems = Systems::default();
@@ -347,30 +914,23 @@ mod test {
         let b = B { val: 2 };
         ecs.add_resource(b);
 
-        fn mutate_a(mut res: ResMut<A>) {
-            let a = res.get_mut().unwrap();
+        fn mutate_a(mut a: ResMut<A>) {
             a.val = 11;
         }
 
-        fn mutate_b(mut res: ResMut<B>) {
-            let b = res.get_mut().unwrap();
-            b.val = 22;
+        fn mutate_b(b: Option<ResMut<B>>) {
+            b.unwrap().val = 22;
         }
 
-        fn mutate_c(mut res: ResMut<C>) {
-            assert_eq!(
-                res.get_mut().unwrap_err(),
-                crate::world::Error::Resources(Error::GetNonExisting("kiwi::resources::test::C"))
-            );
+        fn mutate_c(c: Option<ResMut<C>>) {
+            assert!(c.is_none());
         }
 
-        fn validate_a(res: Res<A>) {
-            let a = res.get().unwrap();
+        fn validate_a(a: Res<A>) {
             assert_eq!(a.val, 11);
         }
 
-        fn validate_b(res: Res<B>) {
-            let b = res.get().unwrap();
+        fn validate_b(b: Res<B>) {
             assert_eq!(b.val, 22);
         }
 
@@ -384,4 +944,138 @@ mod test {
 
         systems.run(&mut ecs);
     }
+
+    #[test]
+    #[should_panic(expected = "Resource kiwi::resources::test::C requested by system \
+                    kiwi::resources::test::res_missing_panics::get_c does not exist")]
+    fn res_missing_panics() {
+        fn get_c(_: Res<C>) {}
+
+        let mut ecs = World::default();
+        let mut systems = Systems::default();
+        systems.add_system(get_c);
+        systems.run(&mut ecs);
+    }
+
+    #[test]
+    fn res_change_detection() {
+        #[derive(Debug, Default)]
+        struct Log(Vec<(bool, bool)>);
+        impl Resource for Log {}
+
+        fn read(a: Res<A>, mut log: ResMut<Log>) {
+            log.0.push((a.is_added(), a.is_changed()));
+        }
+
+        fn write(mut a: ResMut<A>) {
+            if a.val == 2 {
+                a.val = 3;
+            }
+        }
+
+        let mut world = World::default();
+        world.add_resource(A { val: 1 });
+        world.add_resource(Log::default());
+
+        let mut systems = Systems::default();
+        systems.add_system(read);
+        systems.add_system(write);
+
+        systems.run(&mut world);
+        systems.run(&mut world);
+        world.get_resource_mut::<A>().unwrap().val = 2;
+        // `read` runs before `write`, so it sees the write
+        // of `write` only on the next run
+        systems.run(&mut world);
+        systems.run(&mut world);
+        systems.run(&mut world);
+
+        assert_eq!(
+            world.get_resource::<Log>().unwrap().0,
+            [
+                (true, true),
+                (false, false),
+                (false, true),
+                (false, true),
+                (false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn res_ticks_wrap() {
+        let ticks = ResourceTicks::new(u32::MAX);
+        assert!(ticks.is_changed(u32::MAX - 1, u32::MAX));
+        assert!(ticks.is_changed(u32::MAX - 1, 1));
+        assert!(!ticks.is_changed(u32::MAX, 1));
+
+        let ticks = ResourceTicks::new(1);
+        assert!(ticks.is_added(u32::MAX, 1));
+        assert!(ticks.is_added(u32::MAX, 2));
+        assert!(!ticks.is_added(1, 2));
+    }
+
+    #[test]
+    fn non_send_system_param() {
+        use std::rc::Rc;
+
+        #[derive(Debug)]
+        struct Handle(Rc<u32>);
+
+        fn replace(mut handle: NonSendMut<Handle>) {
+            handle.0 = Rc::new(*handle.0 + 1);
+        }
+
+        fn validate(handle: NonSend<Handle>) {
+            assert_eq!(*handle.0, 2);
+        }
+
+        let mut world = World::default();
+        world.add_non_send_resource(Handle(Rc::new(1)));
+
+        let mut access = Access::default();
+        <NonSend<Handle> as SystemParameter>::Fetch::access(&mut access);
+        assert!(access.is_non_send());
+
+        let mut systems = Systems::default();
+        systems.add_system(replace);
+        systems.add_system(validate);
+        systems.run(&mut world);
+
+        assert_eq!(*world.get_non_send_resource::<Handle>().unwrap().0, 2);
+    }
+
+    #[test]
+    fn non_send_dropped_on_other_thread() {
+        let mut world = World::default();
+        world.add_non_send_resource(1u32);
+        // non send resources are leaked instead of being dropped
+        assert!(std::thread::spawn(move || drop(world)).join().is_ok());
+    }
+
+    #[test]
+    fn non_send_system_on_other_thread_panics() {
+        fn read(_: NonSend<u32>) {}
+
+        let mut world = World::default();
+        world.add_non_send_resource(1u32);
+        let mut systems = Systems::default();
+        systems.add_system(read);
+        systems.run(&mut world);
+
+        let result = std::thread::spawn(move || systems.run(&mut world)).join();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn non_send_other_thread_panics() {
+        let mut world = World::default();
+        world.add_non_send_resource(1u32);
+
+        let result = std::thread::spawn(move || {
+            let _ = world.get_non_send_resource::<u32>();
+        })
+        .join();
+        assert!(result.is_err());
+    }
 }
@@ -1,19 +1,814 @@
-use std::any::TypeId;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
-use crate::system::{IntoSystem, System, SystemParameter};
+use crate::{
+    access::Access,
+    condition::{Condition, IntoCondition},
+    executor::{DefaultExecutor, Executor},
+    resources::Resource,
+    system::{IntoSystem, System, SystemError, SystemParameter},
+    world::{UnsafeWorldCell, World},
+};
 
-struct Schedule {
-    systems: Vec<Box<dyn System>>,
+/// What to do with errors returned by systems
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorHandler {
+    /// Logs the error
+    #[default]
+    Log,
+    /// Panics with the error after all systems have run
+    Panic,
+    /// Pushes the error into the [`SystemErrors`] resource
+    Collect,
+    /// Logs the error and disables the failed system
+    Disable,
+}
+
+/// Errors collected with [`ErrorHandler::Collect`]
+#[derive(Debug, Default)]
+pub struct SystemErrors {
+    /// Names of failed systems with their errors
+    pub errors: Vec<(&'static str, SystemError)>,
+}
+
+impl Resource for SystemErrors {}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Systems form a dependency cycle: {0:?}")]
+    DependencyCycle(Vec<&'static str>),
+    #[error("Stage {0} does not exist")]
+    NonExistingStage(&'static str),
+    #[error("Stage {0} already exists")]
+    StageAlreadyExists(&'static str),
+}
+
+/// Unique handle of a system added to a schedule
+/// Ids increase in the order systems are added
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemId(u64);
+
+impl SystemId {
+    pub(crate) fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// System with its ordering constraints and run conditions
+pub struct SystemConfig {
+    system: Box<dyn System>,
+    conditions: Vec<Box<dyn Condition>>,
+    labels: Vec<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+}
+
+/// Marker for [`IntoSystemConfig`] implementation of [`SystemConfig`]
+pub struct SystemConfigMarker;
+
+/// Marker for [`IntoSystemConfig`] implementation of [`System`] types
+pub struct SystemMarker;
+
+pub trait IntoSystemConfig<Marker>: Sized {
+    fn into_config(self) -> SystemConfig;
+
+    /// Adds `label` to the system
+    fn label(self, label: &'static str) -> SystemConfig {
+        let mut config = self.into_config();
+        config.labels.push(label);
+        config
+    }
+
+    /// System will run before all systems with `label`
+    fn before(self, label: &'static str) -> SystemConfig {
+        let mut config = self.into_config();
+        config.before.push(label);
+        config
+    }
+
+    /// System will run after all systems with `label`
+    fn after(self, label: &'static str) -> SystemConfig {
+        let mut config = self.into_config();
+        config.after.push(label);
+        config
+    }
+
+    /// System will run only if `condition` is true
+    /// Conditions are evaluated before system parameters are fetched
+    fn run_if<C, M>(self, condition: C) -> SystemConfig
+    where
+        C: IntoCondition<M>,
+    {
+        let mut config = self.into_config();
+        config.conditions.push(Box::new(condition.into_condition()));
+        config
+    }
+}
+
+impl IntoSystemConfig<SystemConfigMarker> for SystemConfig {
+    fn into_config(self) -> SystemConfig {
+        self
+    }
+}
+
+impl<S: System> IntoSystemConfig<SystemMarker> for S {
+    fn into_config(self) -> SystemConfig {
+        SystemConfig {
+            system: Box::new(self),
+            conditions: vec![],
+            labels: vec![],
+            before: vec![],
+            after: vec![],
+        }
+    }
+}
+
+impl<S, P> IntoSystemConfig<P> for S
+where
+    S: IntoSystem<P>,
+    P: SystemParameter,
+{
+    fn into_config(self) -> SystemConfig {
+        SystemConfig {
+            system: Box::new(self.into_system()),
+            conditions: vec![],
+            labels: vec![],
+            before: vec![],
+            after: vec![],
+        }
+    }
+}
+
+/// Condition attached to all systems with a label
+type SharedCondition = Arc<Mutex<dyn Condition>>;
+
+/// System with its run conditions
+struct ScheduledSystem {
+    id: SystemId,
+    system: Box<dyn System>,
+    conditions: Vec<Box<dyn Condition>>,
+    /// Conditions of the system labels
+    /// Updated on each [`Schedule::build`]
+    label_conditions: Vec<SharedCondition>,
+    /// Access of the system and all its conditions
+    access: Access,
+    /// Disabled systems are skipped
+    enabled: bool,
+    /// Reset when label conditions change
+    is_initialized: bool,
+}
+
+impl ScheduledSystem {
+    fn new(id: SystemId, system: Box<dyn System>, conditions: Vec<Box<dyn Condition>>) -> Self {
+        let mut scheduled = Self {
+            id,
+            system,
+            conditions,
+            label_conditions: vec![],
+            access: Access::default(),
+            enabled: true,
+            is_initialized: false,
+        };
+        scheduled.update_access();
+        scheduled
+    }
+
+    fn update_access(&mut self) {
+        let mut access = self.system.access().clone();
+        for condition in self.conditions.iter() {
+            access.extend(condition.access());
+        }
+        for condition in self.label_conditions.iter() {
+            access.extend(condition.lock().unwrap().access());
+        }
+        self.access = access;
+    }
+}
+
+impl System for ScheduledSystem {
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell) -> Result<(), SystemError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        // Conditions are initialized together with the system
+        // and their accesses are part of the system access
+        let should_run = self
+            .conditions
+            .iter_mut()
+            .all(|condition| condition.evaluate_unsafe(world))
+            && self
+                .label_conditions
+                .iter()
+                .all(|condition| condition.lock().unwrap().evaluate_unsafe(world));
+        if should_run {
+            self.system.run_unsafe(world)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.system.name()
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        self.system.apply_deferred(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        if self.is_initialized {
+            return;
+        }
+        self.system.initialize(world);
+        for condition in self.conditions.iter_mut() {
+            condition.initialize(world);
+        }
+        for condition in self.label_conditions.iter() {
+            condition.lock().unwrap().initialize(world);
+        }
+        self.is_initialized = true;
+    }
+}
+
+#[derive(Debug)]
+struct SystemOrdering {
+    /// Id of the system which also defines insertion order
+    id: SystemId,
+    labels: Vec<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+}
+
+/// Ordered collection of systems
+/// Systems are sorted by their `before`/`after` constraints.
+/// Systems without constraints between them keep insertion order.
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<ScheduledSystem>,
+    orderings: Vec<SystemOrdering>,
+    label_conditions: Vec<(&'static str, SharedCondition)>,
+    ambiguities: Vec<(&'static str, &'static str)>,
+    executor: DefaultExecutor,
+    error_handler: ErrorHandler,
+    is_built: bool,
+}
+
+impl Debug for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "systems num: {}, is_built: {}",
+            self.systems.len(),
+            self.is_built,
+        ))
+    }
 }
 
 impl Schedule {
-    /// Adds system that is run on every [`Systems::run`] call;
+    /// Adds system to the schedule
+    #[tracing::instrument(skip_all)]
+    pub fn add_system<S, M>(&mut self, system: S) -> SystemId
+    where
+        S: IntoSystemConfig<M>,
+    {
+        let config = system.into_config();
+        let id = SystemId::new();
+        self.orderings.push(SystemOrdering {
+            id,
+            labels: config.labels,
+            before: config.before,
+            after: config.after,
+        });
+        self.systems
+            .push(ScheduledSystem::new(id, config.system, config.conditions));
+        self.is_built = false;
+        id
+    }
+
+    /// Removes system from the schedule
+    /// Returns false if the system is not in the schedule
+    #[tracing::instrument(skip_all)]
+    pub fn remove(&mut self, id: SystemId) -> bool {
+        match self.system_index(id) {
+            Some(index) => {
+                self.systems.remove(index);
+                self.orderings.remove(index);
+                self.is_built = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Disabled systems stay in the schedule, but do not run
+    /// Returns false if the system is not in the schedule
+    pub fn set_enabled(&mut self, id: SystemId, enabled: bool) -> bool {
+        match self.system_index(id) {
+            Some(index) => {
+                self.systems[index].enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `None` if the system is not in the schedule
+    pub fn is_enabled(&self, id: SystemId) -> Option<bool> {
+        self.system_index(id)
+            .map(|index| self.systems[index].enabled)
+    }
+
+    pub fn contains(&self, id: SystemId) -> bool {
+        self.system_index(id).is_some()
+    }
+
+    /// Systems with `label` will run only if `condition` is true
+    /// Condition is evaluated before each of these systems
     #[tracing::instrument(skip_all)]
-    pub fn add_system<S, P>(&mut self, system: S)
+    pub fn add_label_condition<C, M>(&mut self, label: &'static str, condition: C)
     where
-        S: IntoSystem<P>,
-        P: SystemParameter,
+        C: IntoCondition<M>,
     {
-        self.systems.push(Box::new(system.into_system()));
+        self.label_conditions
+            .push((label, Arc::new(Mutex::new(condition.into_condition()))));
+        self.is_built = false;
+    }
+
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Names of the systems in the order they run
+    pub fn system_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.systems.iter().map(|system| system.name())
+    }
+
+    /// Pairs of systems with conflicting accesses and without
+    /// explicit order between them
+    /// Such systems run in insertion order
+    pub fn ambiguities(&self) -> &[(&'static str, &'static str)] {
+        &self.ambiguities
+    }
+
+    /// Sorts systems by their ordering constraints
+    /// Returns error if constraints form a cycle
+    #[tracing::instrument(skip_all)]
+    pub fn build(&mut self) -> Result<(), Error> {
+        if self.is_built {
+            return Ok(());
+        }
+
+        let successors = self.successors();
+        let order = self.topological_order(&successors)?;
+
+        let mut new_index = vec![0; order.len()];
+        for (new, old) in order.iter().enumerate() {
+            new_index[*old] = new;
+        }
+
+        let mut systems = std::mem::take(&mut self.systems)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let mut orderings = std::mem::take(&mut self.orderings)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        for old in order.iter() {
+            self.systems.push(systems[*old].take().unwrap());
+            self.orderings.push(orderings[*old].take().unwrap());
+        }
+
+        for (system, ordering) in self.systems.iter_mut().zip(self.orderings.iter()) {
+            system.label_conditions = self
+                .label_conditions
+                .iter()
+                .filter(|(label, _)| ordering.labels.contains(label))
+                .map(|(_, condition)| condition.clone())
+                .collect();
+            system.update_access();
+            system.is_initialized = false;
+        }
+
+        let mut dependencies = vec![vec![]; order.len()];
+        for (old, succ) in successors.iter().enumerate() {
+            for s in succ.iter() {
+                dependencies[new_index[*s]].push(new_index[old]);
+            }
+        }
+
+        self.ambiguities = self.find_ambiguities(&dependencies);
+        for (a, b) in self.ambiguities.iter() {
+            tracing::warn!("Systems {a} and {b} have conflicting accesses, but no explicit order");
+        }
+
+        self.executor.init(&self.systems, &dependencies);
+        self.is_built = true;
+        Ok(())
+    }
+
+    /// Runs all systems once and applies their deferred changes
+    /// Builds schedule if it was changed and initializes new systems
+    ///
+    /// # Panics
+    /// Panics if systems ordering constraints form a cycle
+    #[tracing::instrument(skip_all)]
+    pub fn run(&mut self, world: &mut World) {
+        if let Err(e) = self.build() {
+            panic!("{e}");
+        }
+        // systems are initialized before the executor, because
+        // initialization can change the world
+        for system in self.systems.iter_mut() {
+            system.initialize(world);
+        }
+        let errors = self.executor.run(&mut self.systems, world);
+        for (index, error) in errors {
+            self.handle_error(index, error, world);
+        }
+        self.apply_deferred(world);
+    }
+
+    /// Sets how errors returned by systems are handled
+    pub fn set_error_handler(&mut self, error_handler: ErrorHandler) {
+        self.error_handler = error_handler;
+    }
+
+    fn handle_error(&mut self, index: usize, error: SystemError, world: &mut World) {
+        let name = self.systems[index].name();
+        match self.error_handler {
+            ErrorHandler::Log => tracing::error!("System {name} failed: {error}"),
+            ErrorHandler::Panic => panic!("System {name} failed: {error}"),
+            ErrorHandler::Collect => {
+                if world.get_resource::<SystemErrors>().is_err() {
+                    world.add_resource(SystemErrors::default());
+                }
+                world
+                    .get_resource_mut::<SystemErrors>()
+                    .unwrap()
+                    .errors
+                    .push((name, error));
+            }
+            ErrorHandler::Disable => {
+                tracing::error!("System {name} failed and is disabled: {error}");
+                self.systems[index].enabled = false;
+            }
+        }
+    }
+
+    /// Adds reserved entities to the world and applies deferred
+    /// changes of all systems in the order systems run
+    #[tracing::instrument(skip_all)]
+    pub fn apply_deferred(&mut self, world: &mut World) {
+        world.flush();
+        for system in self.systems.iter_mut() {
+            system.apply_deferred(world);
+        }
+    }
+
+    fn system_index(&self, id: SystemId) -> Option<usize> {
+        self.systems.iter().position(|system| system.id == id)
+    }
+
+    /// Returns systems that should run after each system
+    fn successors(&self) -> Vec<Vec<usize>> {
+        let mut labeled: HashMap<&'static str, Vec<usize>> = HashMap::new();
+        for (i, ordering) in self.orderings.iter().enumerate() {
+            for label in ordering.labels.iter() {
+                labeled.entry(label).or_default().push(i);
+            }
+        }
+
+        let mut successors = vec![vec![]; self.systems.len()];
+        for (i, ordering) in self.orderings.iter().enumerate() {
+            for (label, is_before) in ordering
+                .before
+                .iter()
+                .map(|l| (l, true))
+                .chain(ordering.after.iter().map(|l| (l, false)))
+            {
+                match labeled.get(label) {
+                    Some(systems) => {
+                        for j in systems.iter().filter(|j| **j != i) {
+                            match is_before {
+                                true => successors[i].push(*j),
+                                false => successors[*j].push(i),
+                            }
+                        }
+                    }
+                    None => tracing::warn!(
+                        "System {} is ordered relative to unknown label {label}",
+                        self.systems[i].name()
+                    ),
+                }
+            }
+        }
+        for succ in successors.iter_mut() {
+            succ.sort_unstable();
+            succ.dedup();
+        }
+        successors
+    }
+
+    /// Kahn's algorithm which picks systems with lowest
+    /// id first
+    fn topological_order(&self, successors: &[Vec<usize>]) -> Result<Vec<usize>, Error> {
+        let mut in_degree = vec![0; self.systems.len()];
+        for succ in successors.iter() {
+            for s in succ.iter() {
+                in_degree[*s] += 1;
+            }
+        }
+
+        let mut ready = in_degree
+            .iter()
+            .enumerate()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(i, _)| Reverse((self.orderings[i].id, i)))
+            .collect::<BinaryHeap<_>>();
+
+        let mut order = Vec::with_capacity(self.systems.len());
+        while let Some(Reverse((_, i))) = ready.pop() {
+            order.push(i);
+            for s in successors[i].iter() {
+                in_degree[*s] -= 1;
+                if in_degree[*s] == 0 {
+                    ready.push(Reverse((self.orderings[*s].id, *s)));
+                }
+            }
+        }
+
+        if order.len() == self.systems.len() {
+            Ok(order)
+        } else {
+            Err(Error::DependencyCycle(
+                self.find_cycle(successors, &in_degree),
+            ))
+        }
+    }
+
+    /// Every system left after Kahn's algorithm has a predecessor
+    /// which is also left, so walking predecessors eventually
+    /// loops back into a cycle
+    fn find_cycle(&self, successors: &[Vec<usize>], in_degree: &[usize]) -> Vec<&'static str> {
+        let mut predecessor = vec![None; self.systems.len()];
+        for (i, succ) in successors.iter().enumerate() {
+            for s in succ.iter() {
+                if in_degree[i] != 0 && in_degree[*s] != 0 {
+                    predecessor[*s] = Some(i);
+                }
+            }
+        }
+
+        let mut path = vec![];
+        let mut current = in_degree.iter().position(|degree| *degree != 0).unwrap();
+        while !path.contains(&current) {
+            path.push(current);
+            current = predecessor[current].unwrap();
+        }
+        let start = path.iter().position(|i| *i == current).unwrap();
+        // path goes against the edges, so reverse it and
+        // put the first visited system in front
+        let mut cycle = path[start..]
+            .iter()
+            .rev()
+            .map(|i| self.systems[*i].name())
+            .collect::<Vec<_>>();
+        cycle.rotate_right(1);
+        cycle
+    }
+
+    /// Finds systems with conflicting accesses which are not
+    /// ordered by constraints
+    /// `dependencies` should be in the sorted order
+    fn find_ambiguities(&self, dependencies: &[Vec<usize>]) -> Vec<(&'static str, &'static str)> {
+        // reachable[i][j] is true if i has to run before j
+        let mut reachable = vec![vec![false; self.systems.len()]; self.systems.len()];
+        for (i, deps) in dependencies.iter().enumerate() {
+            for d in deps.iter() {
+                reachable[*d][i] = true;
+                for row in reachable.iter_mut() {
+                    if row[*d] {
+                        row[i] = true;
+                    }
+                }
+            }
+        }
+
+        let mut ambiguities = vec![];
+        for (i, a) in self.systems.iter().enumerate() {
+            for (j, b) in self.systems.iter().enumerate().skip(i + 1) {
+                if !reachable[i][j] && !a.access().is_compatible(b.access()) {
+                    ambiguities.push((a.name(), b.name()));
+                }
+            }
+        }
+        ambiguities
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::resources::{Res, ResMut, Resource};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Order(Vec<&'static str>);
+    impl Resource for Order {}
+
+    fn a(mut order: ResMut<Order>) {
+        order.0.push("a");
+    }
+    fn b(mut order: ResMut<Order>) {
+        order.0.push("b");
+    }
+    fn c(mut order: ResMut<Order>) {
+        order.0.push("c");
+    }
+
+    #[test]
+    fn schedule_insertion_order() {
+        let mut world = World::default();
+        world.add_resource(Order::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_system(a);
+        schedule.add_system(b);
+        schedule.add_system(c);
+        schedule.run(&mut world);
+
+        assert_eq!(world.get_resource::<Order>().unwrap().0, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn schedule_before_after() {
+        let mut world = World::default();
+        world.add_resource(Order::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_system(a.label("a"));
+        schedule.add_system(c.label("c").after("b").before("a"));
+        schedule.add_system(b.label("b"));
+        schedule.run(&mut world);
+
+        assert_eq!(world.get_resource::<Order>().unwrap().0, ["b", "c", "a"]);
+        assert!(schedule.ambiguities().is_empty());
+    }
+
+    #[test]
+    fn schedule_cycle() {
+        let mut schedule = Schedule::default();
+        schedule.add_system(a.label("a").after("c"));
+        schedule.add_system(b.label("b").after("a"));
+        schedule.add_system(c.label("c").after("b"));
+
+        let Err(Error::DependencyCycle(cycle)) = schedule.build() else {
+            panic!("cycle is not detected");
+        };
+        assert_eq!(cycle.len(), 3);
+        assert!(cycle[0].ends_with("::a"));
+        assert!(cycle[1].ends_with("::b"));
+        assert!(cycle[2].ends_with("::c"));
+    }
+
+    #[test]
+    fn schedule_ambiguities() {
+        fn read(_: Res<Order>) {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_system(a.label("a"));
+        schedule.add_system(read.after("a"));
+        schedule.add_system(b);
+        schedule.build().unwrap();
+
+        let ambiguities = schedule.ambiguities();
+        assert_eq!(ambiguities.len(), 2);
+        assert!(ambiguities[0].0.ends_with("::a") && ambiguities[0].1.ends_with("::b"));
+        assert!(ambiguities[1].0.ends_with("::read") && ambiguities[1].1.ends_with("::b"));
+    }
+
+    #[test]
+    fn schedule_run_if() {
+        use crate::condition::{resource_equals, resource_exists};
+
+        #[derive(Debug, PartialEq)]
+        enum Game {
+            Menu,
+            Playing,
+        }
+        impl Resource for Game {}
+
+        let mut world = World::default();
+        world.add_resource(Order::default());
+        world.add_resource(Game::Menu);
+
+        let mut schedule = Schedule::default();
+        schedule.add_system(a.run_if(resource_equals(Game::Playing)));
+        schedule.add_system(b.label("b"));
+        schedule.add_system(c.label("c").run_if(resource_exists::<Game>()));
+        schedule.add_label_condition("b", resource_equals(Game::Menu));
+        schedule.add_label_condition("c", resource_equals(Game::Menu).not());
+
+        schedule.run(&mut world);
+        assert_eq!(world.get_resource::<Order>().unwrap().0, ["b"]);
+
+        *world.get_resource_mut::<Game>().unwrap() = Game::Playing;
+        schedule.run(&mut world);
+        assert_eq!(world.get_resource::<Order>().unwrap().0, ["b", "a", "c"]);
+    }
+
+    fn fail(mut order: ResMut<Order>) -> Result<(), String> {
+        order.0.push("fail");
+        Err("failed".to_string())
+    }
+
+    #[test]
+    fn schedule_error_handler_collect() {
+        let mut world = World::default();
+        world.add_resource(Order::default());
+
+        let mut schedule = Schedule::default();
+        schedule.set_error_handler(ErrorHandler::Collect);
+        schedule.add_system(fail);
+        schedule.add_system(a);
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(
+            world.get_resource::<Order>().unwrap().0,
+            ["fail", "a", "fail", "a"]
+        );
+        let errors = &world.get_resource::<SystemErrors>().unwrap().errors;
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].0.ends_with("::fail"));
+        assert_eq!(errors[0].1.to_string(), "failed");
+    }
+
+    #[test]
+    fn schedule_error_handler_disable() {
+        let mut world = World::default();
+        world.add_resource(Order::default());
+
+        let mut schedule = Schedule::default();
+        schedule.set_error_handler(ErrorHandler::Disable);
+        schedule.add_system(fail);
+        schedule.add_system(a);
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.get_resource::<Order>().unwrap().0, ["fail", "a", "a"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed")]
+    fn schedule_error_handler_panic() {
+        let mut world = World::default();
+        world.add_resource(Order::default());
+
+        let mut schedule = Schedule::default();
+        schedule.set_error_handler(ErrorHandler::Panic);
+        schedule.add_system(fail);
+        schedule.run(&mut world);
+    }
+
+    #[test]
+    fn schedule_remove_and_disable() {
+        let mut world = World::default();
+        world.add_resource(Order::default());
+
+        let mut schedule = Schedule::default();
+        let a_id = schedule.add_system(a.label("a"));
+        let b_id = schedule.add_system(b.after("a"));
+        let c_id = schedule.add_system(c.before("a"));
+        schedule.run(&mut world);
+
+        assert!(schedule.remove(c_id));
+        assert!(!schedule.remove(c_id));
+        assert!(!schedule.contains(c_id));
+        assert!(schedule.set_enabled(a_id, false));
+        assert_eq!(schedule.is_enabled(a_id), Some(false));
+        assert_eq!(schedule.is_enabled(b_id), Some(true));
+        assert_eq!(schedule.is_enabled(c_id), None);
+        schedule.run(&mut world);
+
+        schedule.set_enabled(a_id, true);
+        schedule.run(&mut world);
+
+        assert_eq!(
+            world.get_resource::<Order>().unwrap().0,
+            ["c", "a", "b", "b", "a", "b"]
+        );
     }
 }
@@ -1,9 +1,93 @@
-use std::{fmt::Debug, marker::PhantomData};
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    access::Access,
+    condition::IntoCondition,
+    schedule::{Error, ErrorHandler, IntoSystemConfig, Schedule, SystemId},
+    world::{UnsafeWorldCell, World},
+};
+
+/// Error returned by a system
+/// Errors have to be `Send` because systems can run on other threads
+pub type SystemError = Box<dyn std::error::Error + Send + Sync>;
+
+pub trait System: Send + 'static {
+    /// Initializes and runs the system with exclusive access to the world
+    fn run(&mut self, world: &mut World) -> Result<(), SystemError> {
+        self.initialize(world);
+        // Safe because the world is borrowed exclusively
+        unsafe { self.run_unsafe(world.as_unsafe_world_cell()) }
+    }
+
+    /// Runs the system in the world shared with other systems
+    ///
+    /// # Safety
+    /// - The system has to be initialized in the world
+    /// - Accesses of all systems using the world at the same time
+    ///   have to be compatible
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell) -> Result<(), SystemError>;
+
+    /// Name used in logs and errors
+    fn name(&self) -> &'static str;
+
+    /// Components and resources the system accesses
+    fn access(&self) -> &Access;
 
-use crate::world::World;
+    /// Applies changes deferred during the last run, like [`Commands`]
+    ///
+    /// [`Commands`]: crate::commands::Commands
+    fn apply_deferred(&mut self, world: &mut World);
 
-pub trait System: 'static {
-    fn run(&mut self, world: &mut World);
+    /// Prepares the system to run in the `world`
+    /// Called before the first run, repeated calls do nothing
+    fn initialize(&mut self, _world: &mut World) {}
+}
+
+impl<S: System + ?Sized> System for Box<S> {
+    fn run(&mut self, world: &mut World) -> Result<(), SystemError> {
+        (**self).run(world)
+    }
+
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell) -> Result<(), SystemError> {
+        (**self).run_unsafe(world)
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn access(&self) -> &Access {
+        (**self).access()
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        (**self).apply_deferred(world)
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        (**self).initialize(world)
+    }
+}
+
+/// Return type of a function which can be used as a system
+pub trait IntoSystemResult {
+    fn into_system_result(self) -> Result<(), SystemError>;
+}
+
+impl IntoSystemResult for () {
+    fn into_system_result(self) -> Result<(), SystemError> {
+        Ok(())
+    }
+}
+
+impl<E: Into<SystemError>> IntoSystemResult for Result<(), E> {
+    fn into_system_result(self) -> Result<(), SystemError> {
+        self.map_err(Into::into)
+    }
 }
 
 pub trait SystemParameter: Sized {
@@ -14,13 +98,29 @@ pub trait SystemParameterFetch {
     type Item<'world, 'cache>: SystemParameter<Fetch = Self>;
     type Cache: SystemParameterCache;
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        cache: &'cache Self::Cache,
+    /// # Safety
+    /// Nothing else can access the data registered in
+    /// [`SystemParameterFetch::access`] while the item is alive
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache>;
+
+    /// Registers components and resources the parameter accesses
+    fn access(access: &mut Access);
+
+    /// Applies deferred changes stored in the cache to the world
+    fn apply(_cache: &mut Self::Cache, _world: &mut World) {}
+
+    /// Creates cache before the first fetch
+    /// Can prepare the world for the parameter
+    /// `system` is the name of the system the parameter belongs to
+    fn init_cache(_world: &mut World, _system: &'static str) -> Self::Cache {
+        Self::Cache::empty()
+    }
 }
 
-pub trait SystemParameterCache {
+pub trait SystemParameterCache: Send {
     fn empty() -> Self;
 }
 
@@ -34,13 +134,23 @@ pub trait IntoSystem<Params> {
 }
 
 pub trait SystemParameterFunction<Parameter: SystemParameter>: 'static {
-    fn run(&mut self, params: SystemParameterItem<Parameter>);
+    type Output;
+
+    fn run(&mut self, params: SystemParameterItem<Parameter>) -> Self::Output;
 }
 
+/// Name of the stage [`Systems::add_system`] adds systems to
+pub const UPDATE_STAGE: &str = "Update";
+
+/// Startup systems and ordered stages of systems
+/// Each stage finishes and applies deferred changes of its
+/// systems before the next one starts
+#[derive(Debug)]
 pub struct Systems {
     is_startup: bool,
-    startup_systems: Vec<Box<dyn System>>,
-    systems: Vec<Box<dyn System>>,
+    startup_systems: Schedule,
+    stages: Vec<(&'static str, Schedule)>,
+    error_handler: ErrorHandler,
 }
 
 impl Default for Systems {
@@ -48,116 +158,337 @@ impl Default for Systems {
         Self {
             is_startup: true,
             startup_systems: Default::default(),
-            systems: Default::default(),
+            stages: vec![(UPDATE_STAGE, Default::default())],
+            error_handler: Default::default(),
         }
     }
 }
 
-impl Debug for Systems {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!(
-            "is_startup: {}, startup_systems num: {}, system num: {}",
-            self.is_startup,
-            self.startup_systems.len(),
-            self.systems.len()
-        ))
-    }
-}
-
 impl Systems {
     /// Adds system that is run only on startup
     #[tracing::instrument(skip_all)]
-    pub fn add_startup_system<S, P>(&mut self, system: S)
+    pub fn add_startup_system<S, M>(&mut self, system: S) -> SystemId
+    where
+        S: IntoSystemConfig<M>,
+    {
+        self.startup_systems.add_system(system)
+    }
+
+    /// Adds system to the [`UPDATE_STAGE`]
+    #[tracing::instrument(skip_all)]
+    pub fn add_system<S, M>(&mut self, system: S) -> SystemId
+    where
+        S: IntoSystemConfig<M>,
+    {
+        self.add_system_to_stage(UPDATE_STAGE, system).unwrap()
+    }
+
+    /// Adds system to the `stage`
+    #[tracing::instrument(skip_all)]
+    pub fn add_system_to_stage<S, M>(
+        &mut self,
+        stage: &'static str,
+        system: S,
+    ) -> Result<SystemId, Error>
     where
-        S: IntoSystem<P>,
-        P: SystemParameter,
+        S: IntoSystemConfig<M>,
     {
-        self.startup_systems.push(Box::new(system.into_system()));
+        let index = self.stage_index(stage)?;
+        Ok(self.stages[index].1.add_system(system))
+    }
+
+    /// Removes system from any stage or from startup systems
+    /// Returns false if the system does not exist
+    #[tracing::instrument(skip_all)]
+    pub fn remove(&mut self, id: SystemId) -> bool {
+        self.schedules_mut().any(|schedule| schedule.remove(id))
     }
 
-    /// Adds system that is run on every [`Systems::run`] call;
+    /// Disabled systems are skipped until enabled again
+    /// Returns false if the system does not exist
     #[tracing::instrument(skip_all)]
-    pub fn add_system<S, P>(&mut self, system: S)
+    pub fn set_enabled(&mut self, id: SystemId, enabled: bool) -> bool {
+        self.schedules_mut()
+            .any(|schedule| schedule.set_enabled(id, enabled))
+    }
+
+    /// Returns `None` if the system does not exist
+    pub fn is_enabled(&self, id: SystemId) -> Option<bool> {
+        std::iter::once(&self.startup_systems)
+            .chain(self.stages.iter().map(|(_, schedule)| schedule))
+            .find_map(|schedule| schedule.is_enabled(id))
+    }
+
+    /// Systems with `label` in the [`UPDATE_STAGE`] will run
+    /// only if `condition` is true
+    #[tracing::instrument(skip_all)]
+    pub fn add_label_condition<C, M>(&mut self, label: &'static str, condition: C)
     where
-        S: IntoSystem<P>,
-        P: SystemParameter,
+        C: IntoCondition<M>,
     {
-        self.systems.push(Box::new(system.into_system()));
+        self.add_label_condition_to_stage(UPDATE_STAGE, label, condition)
+            .unwrap();
     }
 
-    /// Runs all the systems
+    /// Systems with `label` in the `stage` will run only
+    /// if `condition` is true
+    #[tracing::instrument(skip_all)]
+    pub fn add_label_condition_to_stage<C, M>(
+        &mut self,
+        stage: &'static str,
+        label: &'static str,
+        condition: C,
+    ) -> Result<(), Error>
+    where
+        C: IntoCondition<M>,
+    {
+        let index = self.stage_index(stage)?;
+        self.stages[index].1.add_label_condition(label, condition);
+        Ok(())
+    }
+
+    /// Adds new stage after all other stages
+    #[tracing::instrument(skip_all)]
+    pub fn add_stage(&mut self, stage: &'static str) -> Result<(), Error> {
+        self.insert_stage(self.stages.len(), stage)
+    }
+
+    /// Adds new stage right before `target`
+    #[tracing::instrument(skip_all)]
+    pub fn add_stage_before(
+        &mut self,
+        target: &'static str,
+        stage: &'static str,
+    ) -> Result<(), Error> {
+        let index = self.stage_index(target)?;
+        self.insert_stage(index, stage)
+    }
+
+    /// Adds new stage right after `target`
+    #[tracing::instrument(skip_all)]
+    pub fn add_stage_after(
+        &mut self,
+        target: &'static str,
+        stage: &'static str,
+    ) -> Result<(), Error> {
+        let index = self.stage_index(target)?;
+        self.insert_stage(index + 1, stage)
+    }
+
+    /// Sets how errors returned by systems in all stages are handled
+    pub fn set_error_handler(&mut self, error_handler: ErrorHandler) {
+        self.error_handler = error_handler;
+        self.startup_systems.set_error_handler(error_handler);
+        for (_, schedule) in self.stages.iter_mut() {
+            schedule.set_error_handler(error_handler);
+        }
+    }
+
+    /// Names of the stages in the order they run
+    pub fn stages(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.stages.iter().map(|(name, _)| *name)
+    }
+
+    /// Sorts systems by their ordering constraints
+    /// Returns error if constraints form a cycle
+    #[tracing::instrument(skip_all)]
+    pub fn build(&mut self) -> Result<(), Error> {
+        self.startup_systems.build()?;
+        for (_, schedule) in self.stages.iter_mut() {
+            schedule.build()?;
+        }
+        Ok(())
+    }
+
+    /// Pairs of systems with conflicting accesses and without
+    /// explicit order between them
+    pub fn ambiguities(&self) -> impl Iterator<Item = &(&'static str, &'static str)> {
+        self.startup_systems.ambiguities().iter().chain(
+            self.stages
+                .iter()
+                .flat_map(|(_, schedule)| schedule.ambiguities().iter()),
+        )
+    }
+
+    /// Updates events and runs all the systems stage by stage
+    ///
+    /// # Panics
+    /// Panics if systems ordering constraints form a cycle
     #[tracing::instrument(skip_all)]
     pub fn run(&mut self, world: &mut World) {
+        world.increment_change_tick();
+        world.update_events();
         if self.is_startup {
-            for system in self.startup_systems.iter_mut() {
-                system.run(world);
-            }
+            self.startup_systems.run(world);
             self.is_startup = false;
         }
 
-        for system in self.systems.iter_mut() {
-            system.run(world);
+        for (_, schedule) in self.stages.iter_mut() {
+            schedule.run(world);
+        }
+    }
+
+    fn schedules_mut(&mut self) -> impl Iterator<Item = &mut Schedule> {
+        std::iter::once(&mut self.startup_systems)
+            .chain(self.stages.iter_mut().map(|(_, schedule)| schedule))
+    }
+
+    fn stage_index(&self, stage: &'static str) -> Result<usize, Error> {
+        self.stages
+            .iter()
+            .position(|(name, _)| *name == stage)
+            .ok_or(Error::NonExistingStage(stage))
+    }
+
+    fn insert_stage(&mut self, index: usize, stage: &'static str) -> Result<(), Error> {
+        if self.stage_index(stage).is_ok() {
+            return Err(Error::StageAlreadyExists(stage));
         }
+        let mut schedule = Schedule::default();
+        schedule.set_error_handler(self.error_handler);
+        self.stages.insert(index, (stage, schedule));
+        Ok(())
     }
 }
 
 impl<S, P> IntoSystem<P> for S
 where
-    S: SystemParameterFunction<P> + 'static,
+    S: SystemParameterFunction<P> + Send + 'static,
+    S::Output: IntoSystemResult,
     P: SystemParameter + 'static,
 {
     type Output = FunctionSystem<S, P>;
 
     fn into_system(self) -> Self::Output {
-        Self::Output {
-            system: self,
-            cache: <<P as SystemParameter>::Fetch as SystemParameterFetch>::Cache::empty(),
-            params: PhantomData,
-        }
+        FunctionSystem::new(self)
     }
 }
 
 pub struct FunctionSystem<S, Params: SystemParameter> {
     system: S,
     cache: <<Params as SystemParameter>::Fetch as SystemParameterFetch>::Cache,
-    params: PhantomData<Params>,
+    access: Access,
+    is_initialized: bool,
+    params: PhantomData<fn() -> Params>,
+}
+
+impl<S, P> FunctionSystem<S, P>
+where
+    S: SystemParameterFunction<P>,
+    P: SystemParameter,
+{
+    pub fn new(system: S) -> Self {
+        let mut access = Access::default();
+        P::Fetch::access(&mut access);
+        Self {
+            system,
+            cache: <<P as SystemParameter>::Fetch as SystemParameterFetch>::Cache::empty(),
+            access,
+            is_initialized: false,
+            params: PhantomData,
+        }
+    }
+
+    /// Initializes parameters cache if it was not initialized yet
+    #[tracing::instrument(skip_all)]
+    pub fn initialize(&mut self, world: &mut World) {
+        if !self.is_initialized {
+            self.cache = P::Fetch::init_cache(world, std::any::type_name::<S>());
+            self.is_initialized = true;
+        }
+    }
+
+    /// Fetches parameters and calls the function
+    /// Initializes the system on the first call
+    #[tracing::instrument(skip_all)]
+    pub fn run_function(&mut self, ecs: &mut World) -> S::Output {
+        self.initialize(ecs);
+        // Safe because the world is borrowed exclusively
+        unsafe { self.run_function_unsafe(ecs.as_unsafe_world_cell()) }
+    }
+
+    /// Fetches parameters from the shared world and calls the function
+    ///
+    /// # Safety
+    /// Same as [`System::run_unsafe`]
+    #[tracing::instrument(skip_all)]
+    pub unsafe fn run_function_unsafe(&mut self, world: UnsafeWorldCell) -> S::Output {
+        // each run gets its own change tick
+        let world = world.with_new_change_tick();
+        let params = P::Fetch::fetch(world, &mut self.cache);
+        self.system.run(params)
+    }
+
+    pub fn access(&self) -> &Access {
+        &self.access
+    }
+
+    /// Applies deferred operations of the parameters
+    #[tracing::instrument(skip_all)]
+    pub fn apply_deferred(&mut self, world: &mut World) {
+        P::Fetch::apply(&mut self.cache, world);
+    }
 }
 
 impl<S, P> System for FunctionSystem<S, P>
 where
-    S: SystemParameterFunction<P> + 'static,
+    S: SystemParameterFunction<P> + Send + 'static,
+    S::Output: IntoSystemResult,
     P: SystemParameter + 'static,
 {
     #[tracing::instrument(skip_all)]
-    fn run(&mut self, ecs: &mut World) {
-        let params = P::Fetch::fetch(ecs, &self.cache);
-        self.system.run(params);
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell) -> Result<(), SystemError> {
+        self.run_function_unsafe(world).into_system_result()
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<S>()
+    }
+
+    fn access(&self) -> &Access {
+        &self.access
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        FunctionSystem::apply_deferred(self, world)
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        FunctionSystem::initialize(self, world)
     }
 }
 
 macro_rules! impl_system_param_func {
     ($($t:ident),*) => {
-        impl<F, $($t),*> SystemParameterFunction<($($t, )*)> for F
+        impl<F, R, $($t),*> SystemParameterFunction<($($t, )*)> for F
         where
-            F: Fn($($t),*) + 'static,
-            F: Fn($(SystemParameterItem<$t>),*) + 'static,
+            F: FnMut($($t),*) -> R + 'static,
+            F: FnMut($(SystemParameterItem<$t>),*) -> R + 'static,
             $($t: SystemParameter),*,
         {
-            fn run(&mut self, params: SystemParameterItem<($($t, )*)>) {
-                // TODO
-                // maybe try with tuple unpacking
-                // to avoid relying of nightly features
-                self.call(params);
+            type Output = R;
+
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            fn run(&mut self, params: SystemParameterItem<($($t, )*)>) -> R {
+                // Calling through a generic function picks the
+                // `FnMut` bound with parameter items
+                fn call<R, $($t),*>(mut f: impl FnMut($($t),*) -> R, $($t: $t),*) -> R {
+                    f($($t),*)
+                }
+                let ($($t, )*) = params;
+                call(self, $($t),*)
             }
         }
     };
 }
 
-impl<F> SystemParameterFunction<()> for F
+impl<F, R> SystemParameterFunction<()> for F
 where
-    F: Fn() + 'static,
+    F: FnMut() -> R + 'static,
 {
-    fn run(&mut self, _: ()) {
+    type Output = R;
+
+    fn run(&mut self, _: ()) -> R {
         self()
     }
 }
@@ -181,12 +512,30 @@ macro_rules! impl_system_param_tuple {
             type Cache = (
                 $($t::Cache),*,
             );
-            fn fetch<'world, 'cache>(
-                ecs: &'world mut World,
-                cache: &'cache <Self as SystemParameterFetch>::Cache
+            unsafe fn fetch<'world, 'cache>(
+                world: UnsafeWorldCell<'world>,
+                cache: &'cache mut <Self as SystemParameterFetch>::Cache
             ) -> Self::Item<'world, 'cache> {
                 (
-                    $($t::fetch(unsafe {  &mut *(ecs as *mut World) }, &cache.$i )),*
+                    $($t::fetch(world, &mut cache.$i )),*
+                    ,
+                )
+            }
+
+            fn access(access: &mut Access) {
+                $($t::access(access);)*
+            }
+
+            fn apply(cache: &mut <Self as SystemParameterFetch>::Cache, world: &mut World) {
+                $($t::apply(&mut cache.$i, world);)*
+            }
+
+            fn init_cache(
+                world: &mut World,
+                system: &'static str,
+            ) -> <Self as SystemParameterFetch>::Cache {
+                (
+                    $($t::init_cache(world, system)),*
                     ,
                 )
             }
@@ -219,17 +568,76 @@ impl SystemParameterFetch for () {
     type Item<'world, 'cache> = ();
     type Cache = ();
 
-    fn fetch<'world, 'cache>(
-        _ecs: &'world mut World,
-        _cache: &'cache (),
+    unsafe fn fetch<'world, 'cache>(
+        _world: UnsafeWorldCell<'world>,
+        _cache: &'cache mut (),
     ) -> Self::Item<'world, 'cache> {
     }
+
+    fn access(_: &mut Access) {}
 }
 
 impl SystemParameterCache for () {
     fn empty() -> Self {}
 }
 
+/// State of a system which persists between runs
+/// Each system has its own value
+#[derive(Debug)]
+pub struct Local<'cache, T: Default + Send + 'static> {
+    value: &'cache mut T,
+}
+
+impl<T: Default + Send + 'static> Deref for Local<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T: Default + Send + 'static> DerefMut for Local<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+    }
+}
+
+impl<T: Default + Send + 'static> SystemParameter for Local<'_, T> {
+    type Fetch = LocalFetch<T>;
+}
+
+#[derive(Debug)]
+pub struct LocalFetch<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T: Default + Send + 'static> SystemParameterFetch for LocalFetch<T> {
+    type Item<'world, 'cache> = Local<'cache, T>;
+    type Cache = LocalCache<T>;
+
+    unsafe fn fetch<'world, 'cache>(
+        _: UnsafeWorldCell<'world>,
+        cache: &'cache mut Self::Cache,
+    ) -> Self::Item<'world, 'cache> {
+        Self::Item {
+            value: &mut cache.value,
+        }
+    }
+
+    fn access(_: &mut Access) {}
+}
+
+#[derive(Debug, Default)]
+pub struct LocalCache<T> {
+    value: T,
+}
+
+impl<T: Default + Send> SystemParameterCache for LocalCache<T> {
+    fn empty() -> Self {
+        Self::default()
+    }
+}
+
 impl_system_param_tuple!((P1, 0));
 impl_system_param_tuple!((P1, 0), (P2, 1));
 impl_system_param_tuple!((P1, 0), (P2, 1), (P3, 2));
@@ -256,12 +664,13 @@ mod test {
             impl SystemParameterFetch for $fetch {
                 type Item<'world, 'cache> = $t;
                 type Cache = ();
-                fn fetch<'world, 'cache>(
-                    _: &'world mut World,
-                    _: &'cache Self::Cache,
+                unsafe fn fetch<'world, 'cache>(
+                    _: UnsafeWorldCell<'world>,
+                    _: &'cache mut Self::Cache,
                 ) -> Self::Item<'world, 'cache> {
                     Default::default()
                 }
+                fn access(_: &mut Access) {}
             }
             impl SystemParameter for $t {
                 type Fetch = $fetch;
@@ -331,4 +740,148 @@ mod test {
         systems.run(&mut ecs);
         assert_eq!(unsafe { VAR }, 3);
     }
+
+    #[test]
+    fn systems_stages() {
+        use crate::resources::{ResMut, Resource};
+
+        #[derive(Debug, Default)]
+        struct Order(Vec<&'static str>);
+        impl Resource for Order {}
+
+        fn pre_update(mut order: ResMut<Order>) {
+            order.0.push("pre_update");
+        }
+        fn update(mut order: ResMut<Order>) {
+            order.0.push("update");
+        }
+        fn post_update(mut order: ResMut<Order>) {
+            order.0.push("post_update");
+        }
+        fn render(mut order: ResMut<Order>) {
+            order.0.push("render");
+        }
+
+        let mut ecs = World::default();
+        ecs.add_resource(Order::default());
+
+        let mut systems = Systems::default();
+        systems.add_stage("Render").unwrap();
+        systems.add_stage_before(UPDATE_STAGE, "PreUpdate").unwrap();
+        systems.add_stage_after(UPDATE_STAGE, "PostUpdate").unwrap();
+
+        assert_eq!(
+            systems.add_stage("Render"),
+            Err(Error::StageAlreadyExists("Render"))
+        );
+        assert_eq!(
+            systems.add_stage_after("FixedUpdate", "Physics"),
+            Err(Error::NonExistingStage("FixedUpdate"))
+        );
+        assert_eq!(
+            systems.stages().collect::<Vec<_>>(),
+            ["PreUpdate", UPDATE_STAGE, "PostUpdate", "Render"]
+        );
+
+        systems.add_system_to_stage("Render", render).unwrap();
+        systems
+            .add_system_to_stage("PostUpdate", post_update)
+            .unwrap();
+        systems.add_system(update);
+        systems
+            .add_system_to_stage("PreUpdate", pre_update)
+            .unwrap();
+        systems.run(&mut ecs);
+
+        assert_eq!(
+            ecs.get_resource::<Order>().unwrap().0,
+            ["pre_update", "update", "post_update", "render"]
+        );
+    }
+
+    #[test]
+    fn systems_local() {
+        use crate::resources::{ResMut, Resource};
+
+        #[derive(Debug, Default)]
+        struct Counts(Vec<u32>);
+        impl Resource for Counts {}
+
+        fn count(mut counter: Local<u32>, mut counts: ResMut<Counts>) {
+            *counter += 1;
+            counts.0.push(*counter);
+        }
+
+        let mut ecs = World::default();
+        ecs.add_resource(Counts::default());
+
+        let mut systems = Systems::default();
+        systems.add_system(count);
+        systems.add_system(count);
+
+        systems.run(&mut ecs);
+        systems.run(&mut ecs);
+        assert_eq!(ecs.get_resource::<Counts>().unwrap().0, [1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn systems_fn_mut() {
+        use crate::resources::{ResMut, Resource};
+
+        #[derive(Debug, Default)]
+        struct Counts(Vec<u32>);
+        impl Resource for Counts {}
+
+        let mut ecs = World::default();
+        ecs.add_resource(Counts::default());
+
+        let mut counter = 10;
+        let mut systems = Systems::default();
+        systems.add_system(move |mut counts: ResMut<Counts>| {
+            counter += 1;
+            counts.0.push(counter);
+        });
+
+        systems.run(&mut ecs);
+        systems.run(&mut ecs);
+        assert_eq!(ecs.get_resource::<Counts>().unwrap().0, [11, 12]);
+    }
+
+    #[test]
+    fn systems_remove_and_disable() {
+        use crate::resources::{ResMut, Resource};
+
+        #[derive(Debug, Default)]
+        struct Counts(Vec<u32>);
+        impl Resource for Counts {}
+
+        let mut ecs = World::default();
+        ecs.add_resource(Counts::default());
+
+        let mut systems = Systems::default();
+        let startup = systems.add_startup_system(|mut c: ResMut<Counts>| {
+            c.0.push(0);
+        });
+        let one = systems.add_system(|mut c: ResMut<Counts>| c.0.push(1));
+        systems.add_stage("PostUpdate").unwrap();
+        let two = systems
+            .add_system_to_stage("PostUpdate", |mut c: ResMut<Counts>| c.0.push(2))
+            .unwrap();
+
+        assert!(systems.set_enabled(startup, false));
+        assert_eq!(systems.is_enabled(startup), Some(false));
+        systems.run(&mut ecs);
+
+        assert!(systems.set_enabled(one, false));
+        systems.run(&mut ecs);
+
+        assert!(systems.remove(two));
+        assert!(!systems.remove(two));
+        assert_eq!(systems.is_enabled(two), None);
+        assert!(!systems.set_enabled(two, true));
+        systems.set_enabled(one, true);
+        systems.run(&mut ecs);
+
+        assert_eq!(ecs.get_resource::<Counts>().unwrap().0, [1, 2, 2, 1]);
+    }
 }
@@ -1,8 +1,12 @@
 use std::{
-    collections::{hash_map::Iter, HashMap, HashSet, VecDeque},
+    collections::{HashMap, HashSet},
     marker::PhantomData,
+    ops::Range,
+    slice::Iter,
 };
 
+use rayon::prelude::*;
+
 use crate::{
     archetype::ArchetypeInfo,
     blobvec::BlobVec,
@@ -56,6 +60,15 @@ impl TableStorage {
         }
     }
 
+    /// Drops all components of the entity and removes it from the table
+    #[tracing::instrument(skip_all)]
+    pub fn drop_entity(&mut self, table_id: TableId, entity: &Entity) -> Result<(), Error> {
+        match self.tables.get_mut(table_id.0) {
+            Some(table) => table.drop_entity(entity),
+            None => Err(Error::TableDoesNotExist),
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn insert_component<T: Component>(
         &mut self,
@@ -151,6 +164,51 @@ impl TableStorage {
         }
     }
 
+    #[tracing::instrument(skip_all)]
+    pub fn query_chunks<I, CT, const L: usize>(
+        &self,
+        table_id_iter: I,
+    ) -> TableStorageChunkIterator<'_, I, CT, L>
+    where
+        I: Iterator<Item = TableId>,
+        CT: ComponentTuple<L>,
+    {
+        TableStorageChunkIterator {
+            storage: self,
+            table_id_iter,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Splits tables into batches of `batch_size` lines and
+    /// runs `f` for each line on the rayon thread pool
+    ///
+    /// # Panics
+    /// Panics if `batch_size` is 0
+    #[tracing::instrument(skip_all)]
+    pub fn par_for_each<I, CT, F, const L: usize>(&self, table_id_iter: I, batch_size: usize, f: F)
+    where
+        I: Iterator<Item = TableId>,
+        CT: ComponentTuple<L> + Send,
+        F: Fn(CT) + Send + Sync,
+    {
+        assert!(batch_size != 0, "batch size should be greater than 0");
+        let batches = table_id_iter
+            .map(|table_id| self.get_table(table_id).unwrap())
+            .flat_map(|table| {
+                (0..table.len())
+                    .step_by(batch_size)
+                    .map(move |start| (table, start..(start + batch_size).min(table.len())))
+            })
+            .collect::<Vec<_>>();
+
+        // Batches never share lines, so each component is accessed
+        // only by one thread.
+        batches.into_par_iter().for_each(|(table, lines)| {
+            table.component_iter_lines::<CT, L>(lines).for_each(&f);
+        });
+    }
+
     #[tracing::instrument(skip_all)]
     fn get_table(&self, table_id: TableId) -> Option<&Table> {
         self.tables.get(table_id.0)
@@ -199,8 +257,11 @@ where
 #[derive(Debug, Default)]
 pub struct Table {
     columns: HashMap<TypeId, BlobVec>,
+    /// Mapping of entities to their lines
     entities: HashMap<Entity, usize>,
-    empty_lines: VecDeque<usize>,
+    /// Entities stored in each line. Lines are kept dense,
+    /// so columns can be accessed as slices.
+    lines: Vec<Entity>,
 }
 
 impl Table {
@@ -226,23 +287,55 @@ impl Table {
             .collect::<Vec<_>>()
     }
 
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn add_entity(&mut self, entity: Entity) {
-        match self.empty_lines.pop_front() {
-            Some(line) => {
-                self.entities.insert(entity, line);
-            }
-            None => {
-                self.entities.insert(entity, self.entities.len());
-                self.allocate_empty_line();
-            }
-        };
+        self.entities.insert(entity, self.lines.len());
+        self.lines.push(entity);
+        self.allocate_empty_line();
     }
 
+    /// Removes entity line from the table
+    /// Components of the entity should already be dropped or
+    /// moved to another table
+    /// The last line of the table is moved in place of the removed one
     #[tracing::instrument(skip_all)]
     pub fn remove_entity(&mut self, entity: &Entity) {
-        self.empty_lines.push_back(self.entities[entity]);
-        self.entities.remove(entity);
+        let line = self.entities.remove(entity).unwrap();
+        for column in self.columns.values_mut() {
+            // # Safety
+            // Line exists for every column
+            unsafe { column.swap_remove_forget(line) };
+        }
+        self.lines.swap_remove(line);
+        if let Some(moved_entity) = self.lines.get(line) {
+            self.entities.insert(*moved_entity, line);
+        }
+    }
+
+    /// Drops all components of the entity and removes its line
+    #[tracing::instrument(skip_all)]
+    pub fn drop_entity(&mut self, entity: &Entity) -> Result<(), Error> {
+        let line = match self.entities.get(entity) {
+            Some(line) => *line,
+            None => Err(Error::NonExistingEntity)?,
+        };
+        for column in self.columns.values_mut() {
+            // # Safety
+            // Line exists for every column and holds initialized component
+            unsafe { column.drop_at(line) };
+        }
+        self.remove_entity(entity);
+        Ok(())
     }
 
     #[tracing::instrument(skip_all)]
@@ -352,16 +445,40 @@ impl Table {
 
     #[tracing::instrument(skip_all)]
     pub fn component_iter<CT, const L: usize>(&self) -> TableIterator<'_, CT, L>
+    where
+        CT: ComponentTuple<L>,
+    {
+        self.component_iter_lines(0..self.lines.len())
+    }
+
+    /// Same as [`Table::component_iter`], but only for the `lines` range
+    #[tracing::instrument(skip_all)]
+    pub fn component_iter_lines<CT, const L: usize>(
+        &self,
+        lines: Range<usize>,
+    ) -> TableIterator<'_, CT, L>
     where
         CT: ComponentTuple<L>,
     {
         let columns = CT::IDS.map(|id| &self.columns[&id]);
         TableIterator {
             columns,
-            entities: self.entities.iter(),
+            line: lines.start,
+            lines: self.lines[lines].iter(),
             _phantom: PhantomData,
         }
     }
+
+    /// Returns all table lines for the components in `CT`
+    /// as slices of components
+    #[tracing::instrument(skip_all)]
+    pub fn component_chunk<CT, const L: usize>(&self) -> CT::Chunk<'_>
+    where
+        CT: ComponentTuple<L>,
+    {
+        let columns = CT::IDS.map(|id| &self.columns[&id]);
+        CT::fetch_chunk(&self.lines, &columns)
+    }
 }
 
 #[derive(Debug)]
@@ -370,7 +487,8 @@ where
     CT: ComponentTuple<L>,
 {
     columns: [&'a BlobVec; L],
-    entities: Iter<'a, Entity, usize>,
+    line: usize,
+    lines: Iter<'a, Entity>,
     _phantom: PhantomData<CT>,
 }
 
@@ -381,9 +499,39 @@ where
     type Item = CT;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.entities
-            .next()
-            .map(|(entity, line)| CT::fetch(*entity, &self.columns, *line))
+        self.lines.next().map(|entity| {
+            let item = CT::fetch(*entity, &self.columns, self.line);
+            self.line += 1;
+            item
+        })
+    }
+}
+
+pub struct TableStorageChunkIterator<'a, I, CT, const L: usize>
+where
+    I: Iterator<Item = TableId>,
+    CT: ComponentTuple<L>,
+{
+    storage: &'a TableStorage,
+    table_id_iter: I,
+    phantom: PhantomData<CT>,
+}
+
+impl<'a, I, CT, const L: usize> Iterator for TableStorageChunkIterator<'a, I, CT, L>
+where
+    I: Iterator<Item = TableId>,
+    CT: ComponentTuple<L>,
+{
+    type Item = CT::Chunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for table_id in self.table_id_iter.by_ref() {
+            let table = self.storage.get_table(table_id).unwrap();
+            if !table.is_empty() {
+                return Some(table.component_chunk::<CT, L>());
+            }
+        }
+        None
     }
 }
 
@@ -452,6 +600,40 @@ mod test {
         );
     }
 
+    #[test]
+    fn table_remove_entity_keeps_lines_dense() {
+        let mut arc = ArchetypeInfo::default();
+        arc.add_component::<u8>().unwrap();
+        arc.add_component::<u16>().unwrap();
+        let mut table = Table::new(&arc);
+
+        let entities = (0..3)
+            .map(|i| {
+                let entity = Entity::from_raw(i, 0);
+                table.add_entity(entity);
+                table.insert_component(&entity, i as u8).unwrap();
+                table.insert_component(&entity, i * 10).unwrap();
+                entity
+            })
+            .collect::<Vec<_>>();
+
+        table.drop_component::<u8>(&entities[0]).unwrap();
+        table.drop_component::<u16>(&entities[0]).unwrap();
+        table.remove_entity(&entities[0]);
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.lines, [entities[2], entities[1]]);
+        assert_eq!(table.get_component::<u8>(&entities[1]), Ok(&1));
+        assert_eq!(table.get_component::<u16>(&entities[1]), Ok(&10));
+        assert_eq!(table.get_component::<u8>(&entities[2]), Ok(&2));
+        assert_eq!(table.get_component::<u16>(&entities[2]), Ok(&20));
+
+        let (lines, u8s, u16s) = table.component_chunk::<(Entity, &u8, &u16), 2>();
+        assert_eq!(lines, [entities[2], entities[1]]);
+        assert_eq!(u8s, [2, 1]);
+        assert_eq!(u16s, [20, 10]);
+    }
+
     #[test]
     fn table_storage_transfer_insert() {
         let mut arc1 = ArchetypeInfo::default();
@@ -497,10 +679,7 @@ mod test {
                 .len(),
             0
         );
-        assert_eq!(
-            table_storage.tables.get(table_id_1.0).unwrap().empty_lines,
-            vec![0]
-        );
+        assert!(table_storage.tables.get(table_id_1.0).unwrap().is_empty());
 
         assert_eq!(
             table_storage
@@ -592,10 +771,7 @@ mod test {
                 .len(),
             0
         );
-        assert_eq!(
-            table_storage.tables.get(table_id_1.0).unwrap().empty_lines,
-            vec![0]
-        );
+        assert!(table_storage.tables.get(table_id_1.0).unwrap().is_empty());
 
         assert_eq!(
             table_storage
@@ -1,14 +1,24 @@
-use std::collections::HashMap;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU32, Ordering};
 
+use crate::access::Access;
 use crate::archetype::{ArchetypeId, ArchetypeInfo, Archetypes};
 use crate::component::{Component, ComponentTuple};
 use crate::entity::{Entity, EntityGenerator};
 use crate::events::{Event, Events};
+use crate::journal::{Change, ChangeCursor, ChangeJournal, StructuralChange};
+use crate::monitor::{MonitorEvent, MonitorId, QueryFilter, QueryMonitors};
+use crate::observer::{IntoObserver, Observers};
 use crate::query::QueryCache;
-use crate::resources::{Resource, Resources};
-use crate::system::{SystemParameter, SystemParameterFetch};
+use crate::removed::Removed;
+use crate::resources::{FromWorld, NonSendResources, Resource, ResourceTicks, Resources};
+use crate::schedule::{IntoSystemConfig, Schedule, SystemId};
+use crate::system::{IntoSystem, System, SystemParameter, SystemParameterFetch};
 use crate::table::{TableId, TableStorage};
+use crate::utils::types::TypeId;
 
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
 pub enum Error {
@@ -22,6 +32,23 @@ pub enum Error {
     RogueArchetype,
     #[error("Entity {0} does not exist")]
     NonExistingEntity(Entity),
+    #[error("Schedule {0} does not exist")]
+    NonExistingSchedule(&'static str),
+    #[error("System {0:?} is not registered")]
+    NonExistingSystem(SystemId),
+    #[error("System {0} failed: {1}")]
+    SystemFailed(&'static str, String),
+    #[error("Monitor {0:?} does not exist")]
+    NonExistingMonitor(MonitorId),
+}
+
+/// System run on demand with [`World::run_system`]
+struct RegisteredSystem(Box<dyn System>);
+
+impl std::fmt::Debug for RegisteredSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.name())
+    }
 }
 
 #[derive(Debug, Default)]
@@ -30,16 +57,120 @@ pub struct World {
     archetypes: Archetypes,
     storage: TableStorage,
     resources: Resources,
+    /// Accessible only from the thread which created the world
+    non_send_resources: NonSendResources,
     /// Mapping of entities to their archetypes
     entity_to_archetype: HashMap<Entity, ArchetypeId>,
     /// Mapping of archetypes to their tables
     archetype_to_table: HashMap<ArchetypeId, TableId>,
+    /// Schedules run with [`World::run_schedule`]
+    schedules: HashMap<&'static str, Schedule>,
+    /// Systems run with [`World::run_system`]
+    registered_systems: HashMap<SystemId, RegisteredSystem>,
+    /// Functions updating each event type added with [`World::add_event`]
+    event_updaters: Vec<fn(&mut World)>,
+    /// [`Observers`] of each event type
+    observers: HashMap<TypeId, Box<dyn Any + Send>>,
+    /// Logs of removed components with at least one reader
+    removed_components: HashMap<TypeId, Events<Removed>>,
+    journal: ChangeJournal,
+    change_tick: AtomicU32,
+    monitors: QueryMonitors,
 }
 
 impl World {
     /// Creates new entity without components
     pub fn create(&mut self) -> Entity {
-        self.entity_generator.create()
+        self.flush();
+        let entity = self.entity_generator.create();
+        self.record_change(entity, Change::Spawned);
+        entity
+    }
+
+    /// Reserves new entity through shared reference
+    /// Entity is added to the world on the next [`World::flush`]
+    pub fn reserve(&self) -> Entity {
+        self.entity_generator.reserve()
+    }
+
+    /// Adds all reserved entities to the world
+    /// Entities are placed into the empty archetype
+    #[tracing::instrument(skip_all)]
+    pub fn flush(&mut self) {
+        if !self.entity_generator.has_reserved() {
+            return;
+        }
+
+        let arch_info = ArchetypeInfo::default();
+        let arch_id = match self.archetypes.get_id(&arch_info) {
+            Some(id) => id,
+            None => self
+                .archetypes
+                .insert(arch_info.clone())
+                .expect("empty archetype should be insertable"),
+        };
+        let table_id = *self
+            .archetype_to_table
+            .entry(arch_id)
+            .or_insert_with(|| self.storage.new_table(&arch_info));
+
+        let storage = &mut self.storage;
+        let entity_to_archetype = &mut self.entity_to_archetype;
+        let mut spawned = vec![];
+        self.entity_generator.flush(|entity| {
+            // table is taken from the storage above, so it exists
+            storage.add_entity(table_id, entity).unwrap();
+            entity_to_archetype.insert(entity, arch_id);
+            spawned.push(entity);
+        });
+        for entity in spawned {
+            self.record_change(entity, Change::Spawned);
+        }
+    }
+
+    /// Removes entity with all its components
+    /// Returns error if entity does not exist
+    #[tracing::instrument(skip_all)]
+    pub fn despawn(&mut self, entity: Entity) -> Result<(), Error> {
+        self.flush();
+        if !self.entity_generator.is_alive(&entity) {
+            Err(Error::NonExistingEntity(entity))?
+        }
+        let mut ids = vec![];
+        if let Some(arch) = self.entity_to_archetype.remove(&entity) {
+            let table_id = match self.archetype_to_table.get(&arch) {
+                Some(table_id) => *table_id,
+                None => Err(Error::RogueArchetype)?,
+            };
+            self.storage.drop_entity(table_id, &entity)?;
+            // component ids are needed only to report the removals
+            if self.journal.is_subscribed()
+                || !self.removed_components.is_empty()
+                || !self.monitors.is_empty()
+            {
+                ids = self
+                    .archetypes
+                    .get_info(arch)?
+                    .as_sorted_vec_of_component_ids();
+            }
+        }
+        self.entity_generator.delete(&entity);
+        self.monitors.transition(entity, Some(&ids), None);
+        for id in ids {
+            self.record_change(entity, Change::ComponentRemoved(id));
+        }
+        self.record_change(entity, Change::Despawned);
+        Ok(())
+    }
+
+    /// Handle to the world which can be shared by systems
+    /// running at the same time
+    pub fn as_unsafe_world_cell(&mut self) -> UnsafeWorldCell<'_> {
+        UnsafeWorldCell::new(self)
+    }
+
+    pub fn entities(&self) -> &EntityGenerator {
+        &self.entity_generator
     }
 
     pub fn entity_component_info(&self, entity: Entity) -> Option<&ArchetypeInfo> {
@@ -56,6 +187,10 @@ impl World {
         entity: Entity,
         component: C,
     ) -> Result<(), Error> {
+        self.flush();
+        if !self.entity_generator.is_alive(&entity) {
+            Err(Error::NonExistingEntity(entity))?
+        }
         match self.entity_to_archetype.get(&entity) {
             Some(arch) => {
                 let old_table_id = match self.archetype_to_table.get(arch) {
@@ -93,6 +228,7 @@ impl World {
                         component,
                     )?
                 };
+                self.record_change(entity, Change::ComponentAdded(C::ID));
             }
             None => {
                 // The entity does not have an associated compoenet
@@ -116,6 +252,7 @@ impl World {
                 self.storage.add_entity(new_table_id, entity)?;
                 self.storage
                     .insert_component(new_table_id, &entity, component)?;
+                self.record_change(entity, Change::ComponentAdded(C::ID));
             }
         }
         Ok(())
@@ -140,6 +277,7 @@ impl World {
     /// Updates a component of the entity
     #[tracing::instrument(skip_all)]
     pub fn get_component_mut<C: Component>(&mut self, entity: Entity) -> Result<&mut C, Error> {
+        self.flush();
         match self.entity_to_archetype.get(&entity) {
             Some(arch) => {
                 let table_id = match self.archetype_to_table.get(arch) {
@@ -157,6 +295,7 @@ impl World {
     /// Returns error if component does not exist
     #[tracing::instrument(skip_all)]
     pub fn remove_component<C: Component>(&mut self, entity: Entity) -> Result<(), Error> {
+        self.flush();
         match self.entity_to_archetype.get(&entity) {
             Some(arch) => {
                 let old_table_id = match self.archetype_to_table.get(arch) {
@@ -191,6 +330,7 @@ impl World {
                         entity,
                     )?
                 };
+                self.record_change(entity, Change::ComponentRemoved(C::ID));
             }
             None => Err(Error::NonExistingEntity(entity))?,
         }
@@ -199,7 +339,22 @@ impl World {
 
     #[tracing::instrument(skip_all)]
     pub fn add_resource<R: Resource>(&mut self, resource: R) {
-        self.resources.add(resource)
+        let tick = self.increment_change_tick();
+        self.resources.add(resource, tick)
+    }
+
+    /// Adds resource created with [`FromWorld`] only if
+    /// the resource does not exist yet
+    #[tracing::instrument(skip_all)]
+    pub fn init_resource<R: Resource + FromWorld>(&mut self) {
+        if !self.resources.contains::<R>() {
+            let resource = R::from_world(self);
+            self.add_resource(resource);
+        }
+    }
+
+    pub fn contains_resource<R: Resource>(&self) -> bool {
+        self.resources.contains::<R>()
     }
 
     #[tracing::instrument(skip_all)]
@@ -207,14 +362,51 @@ impl World {
         self.resources.remove::<R>().map_err(Error::Resources)
     }
 
+    /// Takes the resource out of the world for the duration of `f`,
+    /// so both the world and the resource can be mutated
+    /// The resource is put back after `f` returns, marked as changed,
+    /// and replaces the one `f` might have added
+    #[tracing::instrument(skip_all)]
+    pub fn resource_scope<R: Resource, U>(
+        &mut self,
+        f: impl FnOnce(&mut World, &mut R) -> U,
+    ) -> Result<U, Error> {
+        let (mut resource, mut ticks) = self.resources.take::<R>()?;
+        let result = f(self, &mut resource);
+        ticks.changed = self.increment_change_tick();
+        self.resources.insert(resource, ticks);
+        Ok(result)
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn get_resource<R: Resource>(&self) -> Result<&R, Error> {
         self.resources.get::<R>().map_err(Error::Resources)
     }
 
+    /// Marks the resource as changed
     #[tracing::instrument(skip_all)]
     pub fn get_resource_mut<R: Resource>(&mut self) -> Result<&mut R, Error> {
-        self.resources.get_mut::<R>().map_err(Error::Resources)
+        let tick = self.increment_change_tick();
+        let (resource, ticks) = self.get_resource_mut_with_ticks::<R>()?;
+        ticks.changed = tick;
+        Ok(resource)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn get_resource_with_ticks<R: Resource>(&self) -> Result<(&R, &ResourceTicks), Error> {
+        self.resources
+            .get_with_ticks::<R>()
+            .map_err(Error::Resources)
+    }
+
+    /// Does not mark the resource as changed
+    #[tracing::instrument(skip_all)]
+    pub fn get_resource_mut_with_ticks<R: Resource>(
+        &mut self,
+    ) -> Result<(&mut R, &mut ResourceTicks), Error> {
+        self.resources
+            .get_mut_with_ticks::<R>()
+            .map_err(Error::Resources)
     }
 
     /// # Safety
@@ -226,9 +418,335 @@ impl World {
             .map_err(Error::Resources)
     }
 
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the world
+    #[tracing::instrument(skip_all)]
+    pub fn add_non_send_resource<R: 'static>(&mut self, resource: R) {
+        self.non_send_resources.add(resource)
+    }
+
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the world
+    #[tracing::instrument(skip_all)]
+    pub fn remove_non_send_resource<R: 'static>(&mut self) -> Result<(), Error> {
+        self.non_send_resources
+            .remove::<R>()
+            .map_err(Error::Resources)
+    }
+
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the world
+    #[tracing::instrument(skip_all)]
+    pub fn get_non_send_resource<R: 'static>(&self) -> Result<&R, Error> {
+        self.non_send_resources.get::<R>().map_err(Error::Resources)
+    }
+
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the world
+    #[tracing::instrument(skip_all)]
+    pub fn get_non_send_resource_mut<R: 'static>(&mut self) -> Result<&mut R, Error> {
+        self.non_send_resources
+            .get_mut::<R>()
+            .map_err(Error::Resources)
+    }
+
+    /// Adds event type which is updated with [`World::update_events`]
+    /// Does nothing if the event type already exists
     #[tracing::instrument(skip_all)]
     pub fn add_event<E: Event>(&mut self) {
-        self.resources.add(Events::<E>::default())
+        if self.resources.get::<Events<E>>().is_ok() {
+            return;
+        }
+        self.add_resource(Events::<E>::default());
+        self.event_updaters.push(|world| {
+            // swapping buffers does not mark the events as changed
+            if let Ok((events, _)) = world.get_resource_mut_with_ticks::<Events<E>>() {
+                events.update();
+            }
+        });
+    }
+
+    /// Swaps buffers of all event types
+    /// Called once per [`Systems::run`]
+    ///
+    /// [`Systems::run`]: crate::system::Systems::run
+    #[tracing::instrument(skip_all)]
+    pub fn update_events(&mut self) {
+        for i in 0..self.event_updaters.len() {
+            (self.event_updaters[i])(self);
+        }
+        for removed in self.removed_components.values_mut() {
+            removed.update();
+        }
+    }
+
+    /// Current change tick of the world
+    pub fn change_tick(&self) -> u32 {
+        self.change_tick.load(Ordering::Relaxed)
+    }
+
+    /// Advances change tick of the world
+    /// Returns new change tick
+    pub fn increment_change_tick(&self) -> u32 {
+        self.change_tick
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_add(1)
+    }
+
+    /// Starts recording structural changes of the world
+    /// Subscriber receives changes made after this call
+    pub fn subscribe_changes(&mut self) -> ChangeCursor {
+        self.journal.subscribe()
+    }
+
+    /// Changes are not recorded when there are no subscribers
+    pub fn unsubscribe_changes(&mut self, cursor: ChangeCursor) {
+        self.journal.unsubscribe(cursor)
+    }
+
+    /// Structural changes the subscriber has not read yet
+    pub fn read_changes(
+        &self,
+        cursor: &mut ChangeCursor,
+    ) -> impl Iterator<Item = &StructuralChange> {
+        self.journal.read(cursor)
+    }
+
+    /// Sets maximum number of changes kept for subscribers
+    pub fn set_journal_capacity(&mut self, capacity: usize) {
+        self.journal.set_capacity(capacity)
+    }
+
+    /// Adds monitor collecting entities which start or stop
+    /// matching the `filter`
+    /// Entities matching the filter already are reported as entered
+    /// in the order of their ids
+    #[tracing::instrument(skip_all)]
+    pub fn add_monitor(&mut self, filter: QueryFilter) -> MonitorId {
+        let matching = self
+            .entity_generator
+            .iter_alive()
+            .filter(|entity| {
+                // entities without components have no archetype
+                let components = self
+                    .entity_to_archetype
+                    .get(entity)
+                    .and_then(|arch| self.archetypes.get_info(*arch).ok())
+                    .map(|info| info.as_sorted_vec_of_component_ids())
+                    .unwrap_or_default();
+                filter.matches(&components)
+            })
+            .collect::<Vec<_>>();
+        self.monitors.add(filter, matching.into_iter())
+    }
+
+    /// Returns false if the monitor does not exist
+    pub fn remove_monitor(&mut self, id: MonitorId) -> bool {
+        self.monitors.remove(id)
+    }
+
+    /// Takes events collected by the monitor since the last drain
+    pub fn drain_monitor(&mut self, id: MonitorId) -> Result<Vec<MonitorEvent>, Error> {
+        self.monitors.drain(id).ok_or(Error::NonExistingMonitor(id))
+    }
+
+    /// All structural changes of entities go through here
+    /// Despawned entities are reported to monitors by [`World::despawn`]
+    fn record_change(&mut self, entity: Entity, change: Change) {
+        if let Change::ComponentRemoved(id) = change {
+            if let Some(removed) = self.removed_components.get_mut(&id) {
+                removed.send(Removed(entity));
+            }
+        }
+        if !self.monitors.is_empty() && self.entity_generator.is_alive(&entity) {
+            let new = self
+                .entity_component_info(entity)
+                .map(|info| info.as_sorted_vec_of_component_ids())
+                .unwrap_or_default();
+            let mut old = new.clone();
+            match change {
+                Change::Spawned => self.monitors.transition(entity, None, Some(&new)),
+                Change::ComponentAdded(id) => {
+                    old.retain(|c| *c != id);
+                    self.monitors.transition(entity, Some(&old), Some(&new));
+                }
+                Change::ComponentRemoved(id) => {
+                    old.push(id);
+                    self.monitors.transition(entity, Some(&old), Some(&new));
+                }
+                Change::Despawned => {}
+            }
+        }
+        let tick = self.change_tick();
+        self.journal.record(tick, entity, change);
+    }
+
+    /// Starts logging removals of the component with `id`
+    /// Logs are updated together with events
+    pub fn track_removed_components(&mut self, id: TypeId) {
+        self.removed_components.entry(id).or_default();
+    }
+
+    /// Log of removals of the component with `id` if it is tracked
+    pub fn removed_components(&self, id: TypeId) -> Option<&Events<Removed>> {
+        self.removed_components.get(&id)
+    }
+
+    /// Adds observer which runs each time `E` is triggered
+    #[tracing::instrument(skip_all)]
+    pub fn observe<E, M>(&mut self, observer: impl IntoObserver<E, M>) -> SystemId
+    where
+        E: Event,
+    {
+        self.observers_mut::<E>()
+            .add(None, observer.into_observer())
+    }
+
+    /// Adds observer which runs each time `E` is triggered for `entity`
+    /// Entity observers run before global ones
+    #[tracing::instrument(skip_all)]
+    pub fn observe_entity<E, M>(
+        &mut self,
+        entity: Entity,
+        observer: impl IntoObserver<E, M>,
+    ) -> SystemId
+    where
+        E: Event,
+    {
+        self.observers_mut::<E>()
+            .add(Some(entity), observer.into_observer())
+    }
+
+    /// Returns false if the observer does not exist
+    #[tracing::instrument(skip_all)]
+    pub fn remove_observer<E: Event>(&mut self, id: SystemId) -> bool {
+        self.observers_mut::<E>().remove(id)
+    }
+
+    /// Runs observers of `E` right away in the order they were added
+    /// Triggers of `E` made from within these observers are queued
+    /// and run in the order they were made after the current one
+    #[tracing::instrument(skip_all)]
+    pub fn trigger<E: Event>(&mut self, event: E, entity: Entity) {
+        let observers = self.observers_mut::<E>();
+        if let Some(pending) = observers.pending_mut() {
+            pending.push_back((event, entity));
+            return;
+        }
+        // observers are taken out of the world for the duration
+        // of the trigger, so they can borrow the world mutably
+        let placeholder = observers.placeholder();
+        let observers = std::mem::replace(observers, placeholder);
+        let mut running = RunningObservers {
+            world: self,
+            observers,
+        };
+        let mut pending = VecDeque::from([(event, entity)]);
+        while let Some((event, entity)) = pending.pop_front() {
+            running.observers.trigger(&event, entity, running.world);
+            // observers, removals and triggers made while triggering
+            let placeholder = running.world.observers_mut::<E>();
+            pending.extend(running.observers.merge(placeholder));
+        }
+    }
+
+    fn observers_mut<E: Event>(&mut self) -> &mut Observers<E> {
+        self.observers
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::<Observers<E>>::default())
+            .downcast_mut::<Observers<E>>()
+            .unwrap()
+    }
+
+    /// Adds schedule that can be run with [`World::run_schedule`]
+    /// Replaces existing schedule with the same name
+    #[tracing::instrument(skip_all)]
+    pub fn add_schedule(&mut self, name: &'static str, schedule: Schedule) {
+        self.schedules.insert(name, schedule);
+    }
+
+    /// Adds system to the schedule with `name`
+    /// Creates the schedule if it does not exist
+    #[tracing::instrument(skip_all)]
+    pub fn add_system_to_schedule<S, M>(&mut self, name: &'static str, system: S) -> SystemId
+    where
+        S: IntoSystemConfig<M>,
+    {
+        self.schedules.entry(name).or_default().add_system(system)
+    }
+
+    /// Runs all systems of the schedule with `name` once
+    ///
+    /// # Panics
+    /// Panics if systems ordering constraints form a cycle
+    #[tracing::instrument(skip_all)]
+    pub fn run_schedule(&mut self, name: &'static str) -> Result<(), Error> {
+        // schedule is taken out of the world for the duration of the
+        // run, so its systems can borrow the world mutably
+        let mut schedule = self
+            .schedules
+            .remove(name)
+            .ok_or(Error::NonExistingSchedule(name))?;
+        schedule.run(self);
+        self.schedules.insert(name, schedule);
+        Ok(())
+    }
+
+    /// Registers system which runs only with [`World::run_system`]
+    /// State of the system parameters is kept between runs
+    #[tracing::instrument(skip_all)]
+    pub fn register_system<S, M>(&mut self, system: S) -> SystemId
+    where
+        S: IntoSystem<M>,
+    {
+        let id = SystemId::new();
+        let mut system = system.into_system();
+        system.initialize(self);
+        self.registered_systems
+            .insert(id, RegisteredSystem(Box::new(system)));
+        id
+    }
+
+    /// Removes registered system
+    /// Returns false if the system is not registered
+    #[tracing::instrument(skip_all)]
+    pub fn unregister_system(&mut self, id: SystemId) -> bool {
+        self.registered_systems.remove(&id).is_some()
+    }
+
+    /// Runs registered system once and applies its deferred changes
+    #[tracing::instrument(skip_all)]
+    pub fn run_system(&mut self, id: SystemId) -> Result<(), Error> {
+        // same as with schedules system is taken out of the world
+        // for the duration of the run
+        let mut system = self
+            .registered_systems
+            .remove(&id)
+            .ok_or(Error::NonExistingSystem(id))?;
+        let result = Self::run_and_apply(&mut system.0, self);
+        self.registered_systems.insert(id, system);
+        result
+    }
+
+    /// Runs system once without registering it
+    #[tracing::instrument(skip_all)]
+    pub fn run_system_once<S, M>(&mut self, system: S) -> Result<(), Error>
+    where
+        S: IntoSystem<M>,
+    {
+        Self::run_and_apply(&mut system.into_system(), self)
+    }
+
+    fn run_and_apply(system: &mut impl System, world: &mut World) -> Result<(), Error> {
+        system.initialize(world);
+        let result = system.run(world);
+        world.flush();
+        system.apply_deferred(world);
+        result.map_err(|e| Error::SystemFailed(system.name(), e.to_string()))
     }
 
     #[tracing::instrument(skip_all)]
@@ -261,6 +779,205 @@ impl World {
 
         self.storage.query::<_, CT, L>(table_id_iter)
     }
+
+    /// Runs `f` for every entity matching `CT` on the rayon thread pool
+    /// Matched tables are split into batches of `batch_size` lines
+    #[tracing::instrument(skip_all)]
+    pub fn par_for_each<CT, const L: usize>(&self, batch_size: usize, f: impl Fn(CT) + Send + Sync)
+    where
+        CT: ComponentTuple<L> + Send,
+    {
+        let table_id_iter = self
+            .archetypes
+            .query_ids(&CT::SORTED_IDS)
+            .map(|arch_id| self.archetype_to_table[&arch_id]);
+
+        self.storage
+            .par_for_each::<_, CT, _, L>(table_id_iter, batch_size, f)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn par_for_each_with_cache<CT, const L: usize>(
+        &self,
+        cache: &QueryCache,
+        batch_size: usize,
+        f: impl Fn(CT) + Send + Sync,
+    ) where
+        CT: ComponentTuple<L> + Send,
+    {
+        let table_id_iter = self
+            .archetypes
+            .query_ids_with_cache(&CT::SORTED_IDS, cache)
+            .map(|arch_id| self.archetype_to_table[&arch_id]);
+
+        self.storage
+            .par_for_each::<_, CT, _, L>(table_id_iter, batch_size, f)
+    }
+
+    /// Iterates over tables matching `CT` returning
+    /// components of each table as slices
+    #[tracing::instrument(skip_all)]
+    pub fn query_chunks<CT, const L: usize>(&self) -> impl Iterator<Item = CT::Chunk<'_>> + '_
+    where
+        CT: ComponentTuple<L>,
+    {
+        let table_id_iter = self
+            .archetypes
+            .query_ids(&CT::SORTED_IDS)
+            .map(|arch_id| self.archetype_to_table[&arch_id]);
+
+        self.storage.query_chunks::<_, CT, L>(table_id_iter)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn query_chunks_with_cache<'a, CT, const L: usize>(
+        &'a self,
+        cache: &'a QueryCache,
+    ) -> impl Iterator<Item = CT::Chunk<'a>> + 'a
+    where
+        CT: ComponentTuple<L>,
+    {
+        let table_id_iter = self
+            .archetypes
+            .query_ids_with_cache(&CT::SORTED_IDS, cache)
+            .map(|arch_id| self.archetype_to_table[&arch_id]);
+
+        self.storage.query_chunks::<_, CT, L>(table_id_iter)
+    }
+}
+
+/// Observers of `E` taken out of the world while they run
+/// They are put back on drop, even if one of them panics
+struct RunningObservers<'world, E: Event> {
+    world: &'world mut World,
+    observers: Observers<E>,
+}
+
+impl<E: Event> Drop for RunningObservers<'_, E> {
+    fn drop(&mut self) {
+        let placeholder = self.world.observers_mut::<E>();
+        // triggers which did not run yet are dropped
+        self.observers.merge(placeholder);
+        *placeholder = std::mem::take(&mut self.observers);
+    }
+}
+
+/// Handle to the world shared by systems running at the same time
+/// Unlike `&mut World` it can be copied to each system, so systems
+/// borrow only the data they fetch
+#[derive(Debug, Clone, Copy)]
+pub struct UnsafeWorldCell<'world> {
+    world: *mut World,
+    /// Change tick of the system run using the cell
+    change_tick: u32,
+    phantom: PhantomData<&'world mut World>,
+}
+
+// # Safety
+// Resources and components are `Send + Sync` and users of the cell
+// access only the data described by their compatible accesses.
+// Non send resources check the thread on every access
+unsafe impl Send for UnsafeWorldCell<'_> {}
+unsafe impl Sync for UnsafeWorldCell<'_> {}
+
+impl<'world> UnsafeWorldCell<'world> {
+    pub fn new(world: &'world mut World) -> Self {
+        Self {
+            change_tick: world.change_tick(),
+            world,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Change tick of the system run using the cell
+    /// Other systems sharing the world may advance the tick of the world
+    pub fn change_tick(self) -> u32 {
+        self.change_tick
+    }
+
+    /// Advances change tick of the world
+    /// Returns cell with the new change tick
+    pub fn with_new_change_tick(self) -> Self {
+        Self {
+            // Safe because the change tick is atomic and no user of the
+            // cell borrows the world mutably without an exclusive access
+            change_tick: unsafe { (*self.world).increment_change_tick() },
+            ..self
+        }
+    }
+
+    /// # Safety
+    /// Nothing can mutate the world while the reference is alive
+    pub unsafe fn world(self) -> &'world World {
+        &*self.world
+    }
+
+    /// # Safety
+    /// Nothing else can access the world while the reference is alive
+    pub unsafe fn world_mut(self) -> &'world mut World {
+        &mut *self.world
+    }
+
+    /// # Safety
+    /// The entity generator is used only to reserve entities
+    /// while the reference is alive
+    pub unsafe fn entities(self) -> &'world EntityGenerator {
+        (*self.world).entities()
+    }
+
+    /// # Safety
+    /// Nothing can mutate the resource while the reference is alive
+    pub unsafe fn get_resource_with_ticks<R: Resource>(
+        self,
+    ) -> Result<(&'world R, &'world ResourceTicks), Error> {
+        (*self.world)
+            .resources
+            .get_with_ticks::<R>()
+            .map_err(Error::Resources)
+    }
+
+    /// # Safety
+    /// Nothing else can access the resource while the reference is alive
+    pub unsafe fn get_resource_mut_with_ticks<R: Resource>(
+        self,
+    ) -> Result<(&'world mut R, &'world mut ResourceTicks), Error> {
+        (*self.world)
+            .resources
+            .get_mut_with_ticks_unchecked::<R>()
+            .map_err(Error::Resources)
+    }
+
+    /// # Safety
+    /// Nothing can mutate the resource while the reference is alive
+    ///
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the world
+    pub unsafe fn get_non_send_resource<R: 'static>(self) -> Result<&'world R, Error> {
+        (*self.world)
+            .non_send_resources
+            .get::<R>()
+            .map_err(Error::Resources)
+    }
+
+    /// # Safety
+    /// Nothing else can access the resource while the reference is alive
+    ///
+    /// # Panics
+    /// Panics if called from a thread other than the one
+    /// which created the world
+    pub unsafe fn get_non_send_resource_mut<R: 'static>(self) -> Result<&'world mut R, Error> {
+        (*self.world)
+            .non_send_resources
+            .get_mut_unchecked::<R>()
+            .map_err(Error::Resources)
+    }
+
+    /// # Safety
+    /// Nothing can mutate removal logs while the reference is alive
+    pub unsafe fn removed_components(self, id: TypeId) -> Option<&'world Events<Removed>> {
+        (*self.world).removed_components(id)
+    }
 }
 
 #[derive(Debug)]
@@ -288,11 +1005,17 @@ impl SystemParameterFetch for WorldRefFetch {
     type Item<'world, 'cache> = WorldRef<'world>;
     type Cache = ();
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        _: &'cache Self::Cache,
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        _: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache> {
-        Self::Item { world }
+        Self::Item {
+            world: world.world(),
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.read_world();
     }
 }
 
@@ -327,11 +1050,17 @@ impl SystemParameterFetch for WorldRefMutFetch {
     type Item<'world, 'cache> = WorldRefMut<'world>;
     type Cache = ();
 
-    fn fetch<'world, 'cache>(
-        world: &'world mut World,
-        _: &'cache Self::Cache,
+    unsafe fn fetch<'world, 'cache>(
+        world: UnsafeWorldCell<'world>,
+        _: &'cache mut Self::Cache,
     ) -> Self::Item<'world, 'cache> {
-        Self::Item { world }
+        Self::Item {
+            world: world.world_mut(),
+        }
+    }
+
+    fn access(access: &mut Access) {
+        access.write_world();
     }
 }
 
@@ -452,4 +1181,268 @@ mod tests {
         let expected = [(&1, &2, &3), (&4, &5, &6)];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn world_despawn() {
+        let mut ecs = World::default();
+
+        let empty = ecs.create();
+        let entities = (0..3u8)
+            .map(|i| {
+                let entity = ecs.create();
+                ecs.add_component(entity, i).unwrap();
+                ecs.add_component(entity, i as u16).unwrap();
+                entity
+            })
+            .collect::<Vec<_>>();
+
+        ecs.despawn(empty).unwrap();
+        ecs.despawn(entities[0]).unwrap();
+        assert_eq!(ecs.despawn(empty), Err(Error::NonExistingEntity(empty)));
+        assert_eq!(
+            ecs.despawn(entities[0]),
+            Err(Error::NonExistingEntity(entities[0]))
+        );
+        assert_eq!(
+            ecs.get_component::<u8>(entities[0]),
+            Err(Error::NonExistingEntity(entities[0]))
+        );
+
+        let mut result = ecs.query::<(&u8, &u16), 2>().collect::<Vec<_>>();
+        result.sort_unstable();
+        assert_eq!(result, [(&1, &1), (&2, &2)]);
+
+        assert_eq!(
+            ecs.add_component(empty, 5u8),
+            Err(Error::NonExistingEntity(empty))
+        );
+        assert_eq!(ecs.query::<(&u8,), 1>().count(), 2);
+    }
+
+    #[test]
+    fn world_reserve_and_flush() {
+        use rayon::prelude::*;
+
+        let mut ecs = World::default();
+
+        let despawned = ecs.create();
+        ecs.add_component(despawned, 1u8).unwrap();
+        ecs.despawn(despawned).unwrap();
+
+        let entities = ecs.entities();
+        let reserved = (0..100)
+            .into_par_iter()
+            .map(|_| entities.reserve())
+            .collect::<Vec<_>>();
+        assert!(reserved.iter().all(|e| !ecs.entities().is_alive(e)));
+
+        ecs.flush();
+        assert!(reserved.iter().all(|e| ecs.entities().is_alive(e)));
+        assert!(reserved
+            .iter()
+            .all(|e| ecs.entity_component_info(*e).unwrap().is_empty()));
+
+        // pending id of the despawned entity is reused
+        assert!(!reserved.contains(&despawned));
+        assert!(reserved.contains(&Entity::from_raw(0, 1)));
+        assert_eq!(
+            reserved
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            100
+        );
+
+        let entity = ecs.create();
+        assert!(!reserved.contains(&entity));
+
+        ecs.add_component(reserved[0], 2u8).unwrap();
+        assert_eq!(ecs.get_component::<u8>(reserved[0]), Ok(&2));
+        ecs.despawn(reserved[1]).unwrap();
+        assert!(!ecs.entities().is_alive(&reserved[1]));
+
+        // adding a component flushes the reserved entity first
+        let reserved = ecs.reserve();
+        ecs.add_component(reserved, 3u8).unwrap();
+        ecs.flush();
+        assert_eq!(ecs.get_component::<u8>(reserved), Ok(&3));
+        assert_eq!(ecs.entity_component_info(reserved).unwrap().len(), 1);
+        assert_eq!(ecs.query::<(&u8,), 1>().count(), 2);
+    }
+
+    #[test]
+    fn world_run_schedule() {
+        #[derive(Debug)]
+        struct Counter(u32);
+        impl Resource for Counter {}
+
+        fn add(mut world: WorldRefMut) {
+            world.get_resource_mut::<Counter>().unwrap().0 += 1;
+        }
+
+        let mut ecs = World::default();
+        ecs.add_resource(Counter(0));
+
+        assert_eq!(
+            ecs.run_schedule("FixedUpdate"),
+            Err(Error::NonExistingSchedule("FixedUpdate"))
+        );
+
+        ecs.add_system_to_schedule("FixedUpdate", add);
+        ecs.add_system_to_schedule("FixedUpdate", add);
+        ecs.run_schedule("FixedUpdate").unwrap();
+        ecs.run_schedule("FixedUpdate").unwrap();
+
+        assert_eq!(ecs.get_resource::<Counter>().unwrap().0, 4);
+    }
+
+    #[test]
+    fn world_run_system() {
+        use crate::{commands::Commands, resources::ResMut, system::Local};
+
+        #[derive(Debug)]
+        struct Counter(u32);
+        impl Resource for Counter {}
+
+        fn count(mut local: Local<u32>, mut counter: ResMut<Counter>, mut commands: Commands) {
+            *local += 1;
+            counter.0 = *local;
+            let entity = commands.spawn();
+            commands.add_component(entity, *local as u8);
+        }
+
+        let mut ecs = World::default();
+        ecs.add_resource(Counter(0));
+
+        let id = ecs.register_system(count);
+        ecs.run_system(id).unwrap();
+        ecs.run_system(id).unwrap();
+        assert_eq!(ecs.get_resource::<Counter>().unwrap().0, 2);
+        assert_eq!(ecs.query::<(&u8,), 1>().count(), 2);
+
+        ecs.run_system_once(count).unwrap();
+        assert_eq!(ecs.get_resource::<Counter>().unwrap().0, 1);
+
+        assert!(ecs.unregister_system(id));
+        assert_eq!(ecs.run_system(id), Err(Error::NonExistingSystem(id)));
+
+        let result = ecs.run_system_once(|| Err::<(), _>("failed"));
+        assert!(matches!(result, Err(Error::SystemFailed(_, e)) if e == "failed"));
+    }
+
+    #[test]
+    fn world_init_resource() {
+        use crate::resources::FromWorld;
+
+        #[derive(Debug, Default, PartialEq)]
+        struct Volume(u32);
+        impl Resource for Volume {}
+
+        #[derive(Debug, PartialEq)]
+        struct DoubleVolume(u32);
+        impl Resource for DoubleVolume {}
+        impl FromWorld for DoubleVolume {
+            fn from_world(world: &mut World) -> Self {
+                Self(world.get_resource::<Volume>().unwrap().0 * 2)
+            }
+        }
+
+        let mut ecs = World::default();
+        ecs.init_resource::<Volume>();
+        assert_eq!(ecs.get_resource::<Volume>(), Ok(&Volume(0)));
+
+        ecs.add_resource(Volume(5));
+        ecs.init_resource::<Volume>();
+        assert_eq!(ecs.get_resource::<Volume>(), Ok(&Volume(5)));
+
+        assert!(!ecs.contains_resource::<DoubleVolume>());
+        ecs.init_resource::<DoubleVolume>();
+        assert!(ecs.contains_resource::<DoubleVolume>());
+        assert_eq!(ecs.get_resource::<DoubleVolume>(), Ok(&DoubleVolume(10)));
+    }
+
+    #[test]
+    fn world_resource_scope() {
+        #[derive(Debug, Default)]
+        struct Spawner(Vec<Entity>);
+        impl Resource for Spawner {}
+
+        let mut ecs = World::default();
+        ecs.add_resource(Spawner::default());
+
+        let spawned = ecs
+            .resource_scope(|world, spawner: &mut Spawner| {
+                assert!(!world.contains_resource::<Spawner>());
+                let entity = world.create();
+                world.add_component(entity, 1u8).unwrap();
+                spawner.0.push(entity);
+                entity
+            })
+            .unwrap();
+
+        assert_eq!(ecs.get_resource::<Spawner>().unwrap().0, [spawned]);
+        assert_eq!(ecs.get_component::<u8>(spawned), Ok(&1));
+        ecs.remove_resource::<Spawner>().unwrap();
+        assert!(ecs.resource_scope(|_, _: &mut Spawner| ()).is_err());
+    }
+
+    #[test]
+    fn world_unsafe_cell_change_tick() {
+        let mut ecs = World::default();
+        let cell = ecs.as_unsafe_world_cell();
+        let first = cell.with_new_change_tick();
+        let second = cell.with_new_change_tick();
+        // each cell keeps its own tick while the world advances
+        assert_eq!(first.change_tick(), cell.change_tick() + 1);
+        assert_eq!(second.change_tick(), cell.change_tick() + 2);
+        assert_eq!(ecs.change_tick(), 2);
+    }
+
+    #[test]
+    fn world_unsafe_cell_disjoint_resources() {
+        #[derive(Debug)]
+        struct Health(u32);
+        impl Resource for Health {}
+
+        #[derive(Debug)]
+        struct Mana(u32);
+        impl Resource for Mana {}
+
+        let mut ecs = World::default();
+        ecs.add_resource(Health(1));
+        ecs.add_resource(Mana(2));
+
+        let cell = ecs.as_unsafe_world_cell();
+        // Safe because each resource is borrowed once
+        let (health, _) = unsafe { cell.get_resource_mut_with_ticks::<Health>() }.unwrap();
+        let (mana, _) = unsafe { cell.get_resource_mut_with_ticks::<Mana>() }.unwrap();
+        std::mem::swap(&mut health.0, &mut mana.0);
+
+        assert_eq!(ecs.get_resource::<Health>().unwrap().0, 2);
+        assert_eq!(ecs.get_resource::<Mana>().unwrap().0, 1);
+    }
+
+    #[test]
+    fn world_change_tick_wraps() {
+        use crate::condition::{resource_changed, Condition, IntoCondition};
+
+        #[derive(Debug)]
+        struct Counter(u32);
+        impl Resource for Counter {}
+
+        let mut world = World {
+            change_tick: AtomicU32::new(u32::MAX - 2),
+            ..Default::default()
+        };
+        world.add_resource(Counter(0));
+        let mut changed = resource_changed::<Counter>().into_condition();
+
+        assert!(changed.evaluate(&mut world));
+        assert!(!changed.evaluate(&mut world));
+        world.get_resource_mut::<Counter>().unwrap().0 += 1;
+        assert!(world.change_tick() < 2);
+        assert!(changed.evaluate(&mut world));
+        assert!(!changed.evaluate(&mut world));
+        assert_eq!(world.get_resource::<Counter>().unwrap().0, 1);
+    }
 }